@@ -0,0 +1,90 @@
+//! Módulo de las celdas (cells) del intérprete Forth.
+
+/// Valor de una celda.
+///
+/// Se almacena siempre en 64 bits, pero el intérprete lo ajusta al ancho configurado
+/// con `CellWidth`, de modo que se comporta como un entero de ese ancho.
+pub type Cell = i64;
+
+/// Ancho en bits de las celdas del intérprete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CellWidth {
+    #[default]
+    Bits16,
+    Bits32,
+    Bits64,
+}
+
+impl CellWidth {
+    /// Devuelve el ancho correspondiente a la cantidad de bits indicada, si es 16, 32 o 64.
+    pub fn from_bits(bits: u32) -> Option<Self> {
+        match bits {
+            16 => Some(CellWidth::Bits16),
+            32 => Some(CellWidth::Bits32),
+            64 => Some(CellWidth::Bits64),
+            _ => None,
+        }
+    }
+
+    pub fn bits(self) -> u32 {
+        match self {
+            CellWidth::Bits16 => 16,
+            CellWidth::Bits32 => 32,
+            CellWidth::Bits64 => 64,
+        }
+    }
+
+    /// Devuelve el tamaño en bytes de una celda.
+    pub fn bytes(self) -> usize {
+        self.bits() as usize / 8
+    }
+
+    /// Ajusta un valor al ancho de la celda descartando los bits que no entran,
+    /// como ocurre al desbordar un entero con signo de ese ancho.
+    pub fn wrap(self, value: i64) -> Cell {
+        let shift = 64 - self.bits();
+        (value << shift) >> shift
+    }
+
+    /// Interpreta la celda como un número sin signo del mismo ancho.
+    pub fn to_unsigned(self, value: Cell) -> u64 {
+        let shift = 64 - self.bits();
+        ((value as u64) << shift) >> shift
+    }
+
+    /// Convierte un token en una celda si es un número que entra en el ancho de la celda.
+    pub fn parse(self, token: &str) -> Option<Cell> {
+        let value = token.parse::<i64>().ok()?;
+        (self.wrap(value) == value).then_some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(CellWidth::Bits16.wrap(32768), -32768);
+        assert_eq!(CellWidth::Bits16.wrap(-1), -1);
+        assert_eq!(CellWidth::Bits32.wrap(1 << 31), -(1 << 31));
+        assert_eq!(CellWidth::Bits32.wrap(100_000), 100_000);
+        assert_eq!(CellWidth::Bits64.wrap(i64::MIN), i64::MIN);
+    }
+
+    #[test]
+    fn test_to_unsigned() {
+        assert_eq!(CellWidth::Bits16.to_unsigned(-1), 0xFFFF);
+        assert_eq!(CellWidth::Bits32.to_unsigned(-2), 0xFFFF_FFFE);
+        assert_eq!(CellWidth::Bits64.to_unsigned(-1), u64::MAX);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(CellWidth::Bits16.parse("-32768"), Some(-32768));
+        assert_eq!(CellWidth::Bits16.parse("40000"), None);
+        assert_eq!(CellWidth::Bits32.parse("40000"), Some(40000));
+        assert_eq!(CellWidth::Bits64.parse("foo"), None);
+        assert_eq!(CellWidth::from_bits(8), None);
+    }
+}
@@ -0,0 +1,181 @@
+//! Módulo del espacio de datos (data space) para el intérprete Forth.
+
+use crate::cell::{Cell, CellWidth};
+use crate::error::ForthError;
+
+/// Estructura que representa un espacio de datos direccionable por byte y de capacidad fija.
+///
+/// Las celdas ocupan los bytes que indica su ancho y se almacenan en formato little-endian.
+#[derive(Debug, PartialEq)]
+pub struct DataSpace {
+    data: Vec<u8>,
+    here: usize,
+    cell: CellWidth,
+}
+
+impl DataSpace {
+    /// Crea un espacio de datos con la capacidad en bytes y el ancho de celda indicados,
+    /// inicializado en cero.
+    pub fn new(size: usize, cell: CellWidth) -> Self {
+        DataSpace {
+            data: vec![0; size],
+            here: 0,
+            cell,
+        }
+    }
+
+    /// Devuelve el tamaño en bytes de una celda.
+    pub fn cell_size(&self) -> usize {
+        self.cell.bytes()
+    }
+
+    /// Convierte una celda en una dirección del espacio de datos.
+    ///
+    /// La celda se interpreta como un número sin signo del ancho de la celda.
+    pub fn address(&self, value: Cell) -> usize {
+        self.cell.to_unsigned(value) as usize
+    }
+
+    /// Convierte una dirección del espacio de datos en una celda.
+    pub fn cell_from_address(&self, addr: usize) -> Cell {
+        self.cell.wrap(addr as i64)
+    }
+
+    /// Devuelve la próxima dirección libre del espacio de datos.
+    pub fn here(&self) -> usize {
+        self.here
+    }
+
+    /// Reserva `n` bytes a partir de la próxima dirección libre y devuelve su dirección inicial.
+    ///
+    /// Si no hay lugar suficiente, se retorna un error de "data-space-overflow".
+    pub fn allot(&mut self, n: usize) -> Result<usize, ForthError> {
+        let addr = self.here;
+        if n > self.data.len() - addr {
+            return Err(ForthError::DataSpaceOverflow);
+        }
+        self.here += n;
+        Ok(addr)
+    }
+
+    /// Libera los últimos `n` bytes reservados.
+    ///
+    /// Si se intenta liberar más de lo reservado, se retorna un error de "address-out-of-range".
+    pub fn release(&mut self, n: usize) -> Result<(), ForthError> {
+        if n > self.here {
+            return Err(ForthError::AddressOutOfRange);
+        }
+        self.here -= n;
+        Ok(())
+    }
+
+    /// Alinea la próxima dirección libre al tamaño de una celda.
+    pub fn align(&mut self) -> Result<(), ForthError> {
+        let cell_size = self.cell_size();
+        let padding = (cell_size - self.here % cell_size) % cell_size;
+        self.allot(padding).map(|_| ())
+    }
+
+    /// Lee la celda que comienza en la dirección indicada.
+    ///
+    /// Si la celda no entra en el espacio de datos, se retorna un error de "address-out-of-range".
+    pub fn fetch(&self, addr: usize) -> Result<Cell, ForthError> {
+        self.check_cell(addr)?;
+        let mut bytes = [0u8; 8];
+        bytes[..self.cell_size()].copy_from_slice(&self.data[addr..addr + self.cell_size()]);
+        Ok(self.cell.wrap(i64::from_le_bytes(bytes)))
+    }
+
+    /// Escribe una celda a partir de la dirección indicada.
+    ///
+    /// Si la celda no entra en el espacio de datos, se retorna un error de "address-out-of-range".
+    pub fn store(&mut self, addr: usize, value: Cell) -> Result<(), ForthError> {
+        self.check_cell(addr)?;
+        let cell_size = self.cell_size();
+        self.data[addr..addr + cell_size].copy_from_slice(&value.to_le_bytes()[..cell_size]);
+        Ok(())
+    }
+
+    /// Lee el byte ubicado en la dirección indicada.
+    pub fn fetch_byte(&self, addr: usize) -> Result<u8, ForthError> {
+        self.data
+            .get(addr)
+            .copied()
+            .ok_or(ForthError::AddressOutOfRange)
+    }
+
+    /// Escribe un byte en la dirección indicada.
+    pub fn store_byte(&mut self, addr: usize, value: u8) -> Result<(), ForthError> {
+        let byte = self
+            .data
+            .get_mut(addr)
+            .ok_or(ForthError::AddressOutOfRange)?;
+        *byte = value;
+        Ok(())
+    }
+
+    /// Verifica que una celda completa entre en el espacio de datos a partir de `addr`.
+    fn check_cell(&self, addr: usize) -> Result<(), ForthError> {
+        match addr.checked_add(self.cell_size()) {
+            Some(end) if end <= self.data.len() => Ok(()),
+            _ => Err(ForthError::AddressOutOfRange),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_and_fetch() {
+        let mut space = DataSpace::new(8, CellWidth::Bits16);
+        space.store(2, -300).unwrap();
+        assert_eq!(space.fetch(2), Ok(-300));
+        assert_eq!(space.fetch_byte(2), Ok((-300i16).to_le_bytes()[0]));
+        assert_eq!(space.fetch_byte(4), Ok(0));
+    }
+
+    #[test]
+    fn test_wide_cells() {
+        let mut space = DataSpace::new(16, CellWidth::Bits32);
+        space.store(4, -100_000).unwrap();
+        assert_eq!(space.fetch(4), Ok(-100_000));
+        assert_eq!(space.fetch(13), Err(ForthError::AddressOutOfRange));
+        assert_eq!(space.address(-1), 0xFFFF_FFFF);
+
+        let mut space = DataSpace::new(16, CellWidth::Bits64);
+        space.store(8, i64::MIN).unwrap();
+        assert_eq!(space.fetch(8), Ok(i64::MIN));
+        assert_eq!(
+            space.fetch(space.address(-1)),
+            Err(ForthError::AddressOutOfRange)
+        );
+        assert_eq!(
+            space.store(space.address(-1), 1),
+            Err(ForthError::AddressOutOfRange)
+        );
+        space.allot(1).unwrap();
+        space.align().unwrap();
+        assert_eq!(space.here(), 8);
+    }
+
+    #[test]
+    fn test_allot_and_align() {
+        let mut space = DataSpace::new(8, CellWidth::Bits16);
+        assert_eq!(space.allot(3), Ok(0));
+        space.align().unwrap();
+        assert_eq!(space.here(), 4);
+        assert_eq!(space.allot(5), Err(ForthError::DataSpaceOverflow));
+        space.release(4).unwrap();
+        assert_eq!(space.here(), 0);
+        assert_eq!(space.release(1), Err(ForthError::AddressOutOfRange));
+    }
+
+    #[test]
+    fn test_address_out_of_range() {
+        let mut space = DataSpace::new(4, CellWidth::Bits16);
+        assert_eq!(space.fetch(3), Err(ForthError::AddressOutOfRange));
+        assert_eq!(space.store_byte(4, 1), Err(ForthError::AddressOutOfRange));
+    }
+}
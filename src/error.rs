@@ -0,0 +1,214 @@
+//! Módulo de errores del intérprete Forth.
+
+use crate::cell::Cell;
+use std::fmt;
+
+/// Errores que puede producir el intérprete Forth.
+///
+/// Su representación textual (`Display`) es el mensaje que imprime el intérprete.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ForthError {
+    StackUnderflow,
+    StackOverflow,
+    ReturnStackUnderflow,
+    ReturnStackOverflow,
+    /// Se usó `I`, `J` o `UNLOOP` fuera de un bucle contado.
+    LoopStackUnderflow,
+    DivisionByZero,
+    /// Resultado aritmético que no entra en una celda, con la aritmética verificada activada.
+    ArithmeticOverflow,
+    AddressOutOfRange,
+    DataSpaceOverflow,
+    /// Token que no es una word del diccionario ni un número.
+    UnknownWord(String),
+    InvalidWord,
+    /// Word que solo puede usarse dentro de una definición.
+    CompileOnlyWord,
+    NestedDefinition,
+    /// Falta el token que cierra una estructura, por ejemplo `THEN` para `IF`.
+    MissingTerminator {
+        terminator: &'static str,
+        opener: &'static str,
+    },
+    /// Token de cierre de estructura sin su apertura correspondiente, o `LEAVE` o `UNLOOP`
+    /// fuera de un bucle `DO`.
+    UnexpectedToken,
+    /// Literal `."` sin la comilla de cierre.
+    UnterminatedString,
+    /// Valor que no corresponde a un carácter válido para `EMIT`.
+    InvalidCharacter,
+    /// Falla al escribir en el destino de salida del intérprete.
+    Io(String),
+    /// Excepción lanzada con `THROW` sin un error equivalente en el intérprete.
+    Throw(Cell),
+}
+
+impl ForthError {
+    /// Devuelve el código de excepción ANS que `CATCH` entrega para el error.
+    pub fn code(&self) -> Cell {
+        match self {
+            ForthError::StackOverflow => -3,
+            ForthError::StackUnderflow => -4,
+            ForthError::ReturnStackOverflow => -5,
+            ForthError::ReturnStackUnderflow => -6,
+            ForthError::DataSpaceOverflow => -8,
+            ForthError::AddressOutOfRange => -9,
+            ForthError::DivisionByZero => -10,
+            ForthError::ArithmeticOverflow => -11,
+            ForthError::UnknownWord(_) => -13,
+            ForthError::CompileOnlyWord => -14,
+            ForthError::UnterminatedString => -16,
+            ForthError::MissingTerminator { .. } | ForthError::UnexpectedToken => -22,
+            ForthError::InvalidCharacter => -24,
+            ForthError::LoopStackUnderflow => -26,
+            ForthError::NestedDefinition => -29,
+            ForthError::Io(_) => -37,
+            ForthError::InvalidWord => -32,
+            ForthError::Throw(code) => *code,
+        }
+    }
+
+    /// Devuelve el error que produce `THROW` para un código de excepción.
+    pub fn from_code(code: Cell) -> Self {
+        match code {
+            -3 => ForthError::StackOverflow,
+            -4 => ForthError::StackUnderflow,
+            -5 => ForthError::ReturnStackOverflow,
+            -6 => ForthError::ReturnStackUnderflow,
+            -8 => ForthError::DataSpaceOverflow,
+            -9 => ForthError::AddressOutOfRange,
+            -10 => ForthError::DivisionByZero,
+            -11 => ForthError::ArithmeticOverflow,
+            -13 => ForthError::UnknownWord(String::new()),
+            -14 => ForthError::CompileOnlyWord,
+            -26 => ForthError::LoopStackUnderflow,
+            -32 => ForthError::InvalidWord,
+            -37 => ForthError::Io(String::new()),
+            _ => ForthError::Throw(code),
+        }
+    }
+}
+
+impl fmt::Display for ForthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ForthError::StackUnderflow => write!(f, "stack-underflow"),
+            ForthError::StackOverflow => write!(f, "stack-overflow"),
+            ForthError::ReturnStackUnderflow => write!(f, "return-stack-underflow"),
+            ForthError::ReturnStackOverflow => write!(f, "return-stack-overflow"),
+            ForthError::LoopStackUnderflow => write!(f, "loop-stack-underflow"),
+            ForthError::DivisionByZero => write!(f, "division-by-zero"),
+            ForthError::ArithmeticOverflow => write!(f, "arithmetic-overflow"),
+            ForthError::AddressOutOfRange => write!(f, "address-out-of-range"),
+            ForthError::DataSpaceOverflow => write!(f, "data-space-overflow"),
+            ForthError::UnknownWord(_) => write!(f, "?"),
+            ForthError::InvalidWord => write!(f, "invalid-word"),
+            ForthError::CompileOnlyWord => write!(f, "compile-only-word"),
+            ForthError::NestedDefinition => {
+                write!(f, "Syntax error: nested definitions are not allowed")
+            }
+            ForthError::MissingTerminator { terminator, opener } => {
+                write!(f, "Missing {} for {}", terminator, opener)
+            }
+            ForthError::UnexpectedToken => write!(f, "Unexpected token in definition"),
+            ForthError::UnterminatedString => write!(f, "Missing closing quote for .\""),
+            ForthError::InvalidCharacter => {
+                write!(f, "Valor para EMIT no es un carácter válido")
+            }
+            ForthError::Io(message) => write!(f, "io-error: {}", message),
+            ForthError::Throw(code) => write!(f, "exception {}", code),
+        }
+    }
+}
+
+impl std::error::Error for ForthError {}
+
+/// Posición de un token dentro del código fuente, con línea y columna contadas desde 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Error del intérprete acompañado del lugar del código fuente en el que se produjo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceError {
+    pub error: ForthError,
+    /// Posición del token que produjo el error: el de la definición en ejecución más
+    /// interna, o el de la entrada si el error no ocurrió dentro de una definición.
+    pub span: Span,
+    /// Token que produjo el error.
+    pub token: String,
+    /// Words definidas por el usuario que estaban en ejecución, de la más interna a la más externa.
+    pub backtrace: Vec<String>,
+}
+
+impl SourceError {
+    /// Describe el error indicando el archivo, la posición, el token y las words en ejecución.
+    pub fn report(&self, filename: &str) -> String {
+        let mut report = format!(
+            "{}:{}: {} en `{}`",
+            filename, self.span, self.error, self.token
+        );
+        for name in &self.backtrace {
+            report.push_str(&format!("\n    en {}", name));
+        }
+        report
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl std::error::Error for SourceError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_preserves_messages() {
+        assert_eq!(ForthError::StackUnderflow.to_string(), "stack-underflow");
+        assert_eq!(ForthError::UnknownWord("FOO".to_string()).to_string(), "?");
+        assert_eq!(
+            ForthError::MissingTerminator {
+                terminator: "THEN",
+                opener: "IF"
+            }
+            .to_string(),
+            "Missing THEN for IF"
+        );
+    }
+
+    #[test]
+    fn test_source_error_report() {
+        let error = SourceError {
+            error: ForthError::StackUnderflow,
+            span: Span { line: 3, column: 5 },
+            token: "+".to_string(),
+            backtrace: vec!["INNER".to_string(), "OUTER".to_string()],
+        };
+        assert_eq!(error.to_string(), "stack-underflow");
+        assert_eq!(
+            error.report("prog.fth"),
+            "prog.fth:3:5: stack-underflow en `+`\n    en INNER\n    en OUTER"
+        );
+    }
+
+    #[test]
+    fn test_code_round_trip() {
+        for code in [-3, -4, -10, -13, -37, 42] {
+            assert_eq!(ForthError::from_code(code).code(), code);
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Módulo de fuentes de entrada para el intérprete Forth.
+
+use std::io::{self, BufRead, Read};
+
+/// Fuente de entrada que lee de la entrada estándar de a un byte.
+///
+/// A diferencia de envolver `stdin` en un `BufReader`, no se adelanta a leer más de lo
+/// necesario, así que las líneas que siguen quedan disponibles para quien comparta la
+/// entrada estándar con el intérprete (por ejemplo, el modo interactivo).
+#[derive(Debug, Default)]
+pub struct StdinInput {
+    byte: [u8; 1],
+    available: bool,
+}
+
+impl Read for StdinInput {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let available = self.fill_buf()?;
+        if available.is_empty() {
+            return Ok(0);
+        }
+        buf[0] = available[0];
+        self.consume(1);
+        Ok(1)
+    }
+}
+
+impl BufRead for StdinInput {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if !self.available {
+            self.available = io::stdin().read(&mut self.byte)? == 1;
+        }
+        Ok(if self.available { &self.byte } else { &[] })
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if amt > 0 {
+            self.available = false;
+        }
+    }
+}
@@ -1,32 +1,164 @@
 //! Módulo para la implementación de un intérprete del lenguaje Forth.
 
+use crate::cell::{Cell, CellWidth};
+use crate::data_space::DataSpace;
+use crate::error::{ForthError, SourceError, Span};
+use crate::input::StdinInput;
 use crate::stack::Stack;
-use crate::word::Word;
+use crate::word::{Body, NativeWord, SelfReference, Token, Word};
 use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
 use std::rc::Rc;
 
+/// Capacidad en bytes del espacio de datos del intérprete.
+const DATA_SPACE_SIZE: usize = 16 * 1024;
+
+/// Words que el compilador reconoce por su nombre dentro de una definición en lugar de
+/// buscarlas en el diccionario.
+const COMPILER_WORDS: &[&str] = &[
+    "IF", "DO", "BEGIN", "TO", ".\"", "LITERAL", "RECURSE", "[']",
+];
+
+/// Words que cierran (o continúan) una estructura de control abierta.
+const STRUCTURE_TERMINATORS: &[&str] = &[
+    "ELSE", "THEN", "LOOP", "+LOOP", "UNTIL", "AGAIN", "WHILE", "REPEAT",
+];
+
+/// Cantidad máxima por defecto de niveles de anidamiento: llamadas a words definidas por el
+/// usuario y estructuras de control en ejecución.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 256;
+
+/// Tamaño por defecto, en bytes, de las pilas de datos y de retorno del binario.
+pub const DEFAULT_STACK_SIZE_IN_BYTES: usize = 128 * 1024;
+
+/// Pila del hilo que necesita el intérprete además de la que usan los niveles de anidamiento.
+const BASE_THREAD_STACK_SIZE: usize = 256 * 1024;
+
+/// Pila del hilo que se reserva por cada nivel de anidamiento permitido.
+///
+/// Alcanza para el nivel más costoso (una llamada a través de `EXECUTE` o `DOES>`) incluso
+/// en una compilación sin optimizaciones. Con el límite por defecto, el total entra en los
+/// 2 MiB de pila de un hilo creado con `std::thread::spawn`.
+const THREAD_STACK_PER_CALL: usize = 7 * 1024;
+
+/// Señal pendiente que interrumpe la ejecución de la secuencia de words en curso.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ControlSignal {
+    /// Producida por `LEAVE`: abandona el bucle contado más interno.
+    Leave,
+    /// Producida por `EXIT`: abandona la word definida por el usuario en ejecución.
+    Exit,
+}
+
+/// Entrada del diccionario: la word y su marca `IMMEDIATE`.
+#[derive(Debug)]
+struct Entry {
+    word: Rc<Word>,
+    /// Indica si la word se ejecuta al aparecer dentro de una definición en lugar de compilarse.
+    immediate: bool,
+}
+
 /// Estructura que representa el intérprete Forth.
 pub struct Interpreter {
     stack: Stack,
-    dict: HashMap<String, Rc<Word>>,
-    compiling: Option<(String, Vec<Rc<Word>>)>,
-    tokens: Vec<String>,
+    return_stack: Stack,
+    data_space: DataSpace,
+    dict: HashMap<String, Entry>,
+    /// Nombre de la última word agregada al diccionario.
+    last_defined: Option<String>,
+    /// Words a las que se les asignó un execution token, indexadas por el mismo.
+    xts: Vec<Rc<Word>>,
+    /// Token de la word compilada más interna que falló en el último error.
+    error_token: Option<Token>,
+    /// Words que la word inmediata en ejecución agrega con `POSTPONE` a la definición en curso.
+    postponed: Vec<Rc<Word>>,
+    /// Cierre de estructura (`THEN`, `LOOP`, ...) ejecutado con `POSTPONE` por una word
+    /// inmediata, pendiente para la estructura que se está compilando.
+    postponed_terminator: Option<String>,
+    compiling: Option<(String, Body)>,
+    /// Posición, dentro de la definición en curso, en la que aparece `DOES>`, y su token.
+    does_index: Option<(usize, Token)>,
+    /// Nombre y dirección del campo de datos de la última word creada con `CREATE`.
+    last_created: Option<(String, usize)>,
+    /// Referencia a la definición en curso que compila `RECURSE`.
+    self_reference: SelfReference,
+    /// Indica si la definición en curso fue marcada con `RECURSIVE`.
+    recursive: bool,
+    /// Cantidad de bucles `DO` abiertos en la estructura que se está compilando.
+    open_do_loops: usize,
+    /// Definiciones de usuario en ejecución, de la más externa a la más interna.
+    call_stack: Vec<Rc<Word>>,
+    /// Cantidad de secuencias de words (definiciones y estructuras de control) en ejecución.
+    nesting: usize,
+    /// Definiciones que estaban en ejecución cuando se produjo el último error.
+    backtrace: Option<Vec<Rc<Word>>>,
+    max_call_depth: usize,
+    /// Indica si los desbordes aritméticos producen un error en lugar de dar la vuelta.
+    checked_arithmetic: bool,
+    /// Indica si `AND`, `OR`, `XOR` e `INVERT` operan sobre flags en lugar de bits.
+    logical_flags: bool,
+    tokens: Vec<Token>,
     token_index: usize,
-    saved_cond: Option<i16>,
+    saved_cond: Option<Cell>,
     last_was_dot_quote: bool,
+    /// Pila de control de bucles: cada entrada es `(índice, límite)`.
+    loop_stack: Vec<(Cell, Cell)>,
+    pending: Option<ControlSignal>,
+    /// Destino de lo que imprimen `.`, `EMIT`, `CR` y `."`.
+    output: Box<dyn Write>,
+    /// Fuente de la que leen `KEY`, `KEY?` y `ACCEPT`.
+    input: Box<dyn BufRead>,
+    /// Ancho de las celdas de las pilas y del espacio de datos.
+    cell: CellWidth,
 }
 
 impl Interpreter {
     /// Crea un intérprete con la capacidad de una pila de tamaño especificado.
+    ///
+    /// La pila de retorno recibe la misma capacidad que la pila de datos.
     pub fn new(stack_size: usize) -> Self {
+        Self::with_return_stack_size(stack_size, stack_size)
+    }
+
+    /// Crea un intérprete indicando por separado la capacidad de la pila de datos y la de retorno.
+    pub fn with_return_stack_size(stack_size: usize, return_stack_size: usize) -> Self {
+        Self::with_cell_width(stack_size, return_stack_size, CellWidth::default())
+    }
+
+    /// Crea un intérprete con las capacidades de pila indicadas y celdas del ancho especificado.
+    pub fn with_cell_width(stack_size: usize, return_stack_size: usize, cell: CellWidth) -> Self {
         let mut interpreter = Self {
-            stack: Stack::new(stack_size),
+            stack: Stack::with_cell_width(stack_size, cell),
+            return_stack: Stack::with_cell_width(return_stack_size, cell),
+            data_space: DataSpace::new(DATA_SPACE_SIZE, cell),
             dict: HashMap::new(),
+            last_defined: None,
+            xts: Vec::new(),
+            postponed: Vec::new(),
+            postponed_terminator: None,
             compiling: None,
+            does_index: None,
+            last_created: None,
+            self_reference: SelfReference::default(),
+            recursive: false,
+            open_do_loops: 0,
+            call_stack: Vec::new(),
+            backtrace: None,
+            error_token: None,
+            nesting: 0,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            checked_arithmetic: false,
+            logical_flags: false,
             token_index: 0,
             tokens: Vec::new(),
             saved_cond: None,
             last_was_dot_quote: false,
+            loop_stack: Vec::new(),
+            pending: None,
+            output: Box::new(io::stdout()),
+            input: Box::new(StdinInput::default()),
+            cell,
         };
 
         interpreter.register_builtin_operations();
@@ -34,6 +166,71 @@ impl Interpreter {
         interpreter
     }
 
+    /// Descarta el estado de ejecución tras un error, como hace `ABORT` en Forth.
+    ///
+    /// Vacía las pilas y abandona la definición en curso, pero conserva el diccionario
+    /// y el espacio de datos.
+    pub fn abort(&mut self) {
+        self.stack.truncate(0);
+        self.return_stack.truncate(0);
+        self.loop_stack.clear();
+        self.compiling = None;
+        self.does_index = None;
+        self.recursive = false;
+        self.call_stack.clear();
+        self.nesting = 0;
+        self.backtrace = None;
+        self.error_token = None;
+        self.postponed.clear();
+        self.postponed_terminator = None;
+        self.pending = None;
+        self.saved_cond = None;
+        self.last_was_dot_quote = false;
+    }
+
+    /// Establece la cantidad máxima de niveles de anidamiento: cada llamada a una word
+    /// definida por el usuario y cada estructura de control en ejecución ocupa un nivel.
+    ///
+    /// Superar este límite produce un error de "return-stack-overflow". Cada nivel usa la
+    /// pila del hilo que ejecuta el intérprete, así que ese hilo debe tener al menos la pila
+    /// que indica `thread_stack_size` para el límite elegido.
+    pub fn set_max_call_depth(&mut self, max_call_depth: usize) {
+        self.max_call_depth = max_call_depth;
+    }
+
+    /// Devuelve el tamaño en bytes de la pila que necesita el hilo que ejecuta un intérprete
+    /// con el límite de niveles de anidamiento indicado.
+    pub fn thread_stack_size(max_call_depth: usize) -> usize {
+        max_call_depth
+            .saturating_mul(THREAD_STACK_PER_CALL)
+            .saturating_add(BASE_THREAD_STACK_SIZE)
+    }
+
+    /// Establece si `+`, `-`, `*` y `/` verifican los desbordes.
+    ///
+    /// Por defecto los resultados que no entran en una celda dan la vuelta, como en Forth
+    /// clásico; con la verificación activada producen un error de "arithmetic-overflow".
+    pub fn set_checked_arithmetic(&mut self, checked: bool) {
+        self.checked_arithmetic = checked;
+    }
+
+    /// Establece si `AND`, `OR`, `XOR` e `INVERT` operan sobre flags.
+    ///
+    /// Por defecto operan bit a bit, como en Forth estándar. En modo de flags, cualquier
+    /// valor distinto de cero se toma como verdadero y el resultado es siempre -1 o 0.
+    pub fn set_logical_flags(&mut self, logical_flags: bool) {
+        self.logical_flags = logical_flags;
+    }
+
+    /// Agrega al diccionario una operación predefinida con el nombre indicado.
+    fn register_builtin(&mut self, name: &str) {
+        let entry = Entry {
+            word: Rc::new(Word::Builtin(name.to_string())),
+            immediate: false,
+        };
+        self.dict.insert(name.to_string(), entry);
+    }
+
     /// Registra las operaciones básicas en el diccionario.
     ///
     /// Este método organiza las operaciones en categorías como aritméticas,
@@ -41,126 +238,356 @@ impl Interpreter {
     fn register_builtin_operations(&mut self) {
         self.register_arithmetic_operations();
         self.register_stack_operations();
+        self.register_return_stack_operations();
         self.register_logical_operations();
         self.register_control_flow_operations();
+        self.register_compiler_operations();
+        self.register_exception_operations();
+        self.register_memory_operations();
         self.register_output_operations();
+        self.register_input_operations();
     }
 
     fn register_arithmetic_operations(&mut self) {
-        self.dict
-            .insert("+".to_string(), Rc::new(Word::Builtin("+".to_string())));
-        self.dict
-            .insert("-".to_string(), Rc::new(Word::Builtin("-".to_string())));
-        self.dict
-            .insert("*".to_string(), Rc::new(Word::Builtin("*".to_string())));
-        self.dict
-            .insert("/".to_string(), Rc::new(Word::Builtin("/".to_string())));
+        self.register_builtin("+");
+        self.register_builtin("-");
+        self.register_builtin("*");
+        self.register_builtin("/");
+        self.register_builtin("MOD");
+        self.register_builtin("/MOD");
+        self.register_builtin("*/");
+        self.register_builtin("*/MOD");
+        self.register_builtin("FM/MOD");
+        self.register_builtin("SM/REM");
+        self.register_builtin("NEGATE");
+        self.register_builtin("ABS");
+        self.register_builtin("MIN");
+        self.register_builtin("MAX");
+        self.register_builtin("1+");
+        self.register_builtin("1-");
+        self.register_builtin("2*");
+        self.register_builtin("2/");
     }
 
     fn register_stack_operations(&mut self) {
-        self.dict
-            .insert("DUP".to_string(), Rc::new(Word::Builtin("DUP".to_string())));
-        self.dict.insert(
-            "SWAP".to_string(),
-            Rc::new(Word::Builtin("SWAP".to_string())),
-        );
-        self.dict.insert(
-            "DROP".to_string(),
-            Rc::new(Word::Builtin("DROP".to_string())),
-        );
-        self.dict
-            .insert("ROT".to_string(), Rc::new(Word::Builtin("ROT".to_string())));
-        self.dict.insert(
-            "OVER".to_string(),
-            Rc::new(Word::Builtin("OVER".to_string())),
-        );
+        self.register_builtin("DUP");
+        self.register_builtin("SWAP");
+        self.register_builtin("DROP");
+        self.register_builtin("ROT");
+        self.register_builtin("OVER");
+        self.register_builtin("NIP");
+        self.register_builtin("TUCK");
+        self.register_builtin("PICK");
+        self.register_builtin("ROLL");
+        self.register_builtin("-ROT");
+        self.register_builtin("?DUP");
+        self.register_builtin("2DUP");
+        self.register_builtin("2DROP");
+        self.register_builtin("2SWAP");
+        self.register_builtin("2OVER");
+        self.register_builtin("DEPTH");
+    }
+
+    fn register_return_stack_operations(&mut self) {
+        self.register_builtin(">R");
+        self.register_builtin("R>");
+        self.register_builtin("R@");
+        self.register_builtin("2>R");
+        self.register_builtin("2R>");
+        self.register_builtin("2R@");
     }
 
     fn register_logical_operations(&mut self) {
-        self.dict
-            .insert("NOT".to_string(), Rc::new(Word::Builtin("NOT".to_string())));
-        self.dict
-            .insert("AND".to_string(), Rc::new(Word::Builtin("AND".to_string())));
-        self.dict
-            .insert("OR".to_string(), Rc::new(Word::Builtin("OR".to_string())));
-        self.dict
-            .insert("=".to_string(), Rc::new(Word::Builtin("=".to_string())));
-        self.dict
-            .insert("<".to_string(), Rc::new(Word::Builtin("<".to_string())));
-        self.dict
-            .insert(">".to_string(), Rc::new(Word::Builtin(">".to_string())));
+        self.register_builtin("NOT");
+        self.register_builtin("AND");
+        self.register_builtin("OR");
+        self.register_builtin("XOR");
+        self.register_builtin("INVERT");
+        self.register_builtin("LSHIFT");
+        self.register_builtin("RSHIFT");
+        self.register_builtin("=");
+        self.register_builtin("<");
+        self.register_builtin(">");
+        self.register_builtin("<>");
+        self.register_builtin("<=");
+        self.register_builtin(">=");
+        self.register_builtin("0=");
+        self.register_builtin("0<");
+        self.register_builtin("0>");
+        self.register_builtin("U<");
+        self.register_builtin("U>");
+        self.register_builtin("WITHIN");
     }
 
     fn register_control_flow_operations(&mut self) {
-        self.dict
-            .insert("IF".to_string(), Rc::new(Word::Builtin("IF".to_string())));
-        self.dict.insert(
-            "ELSE".to_string(),
-            Rc::new(Word::Builtin("ELSE".to_string())),
-        );
-        self.dict.insert(
-            "THEN".to_string(),
-            Rc::new(Word::Builtin("THEN".to_string())),
-        );
+        self.register_builtin("IF");
+        self.register_builtin("ELSE");
+        self.register_builtin("THEN");
+        self.register_builtin("I");
+        self.register_builtin("J");
+        self.register_builtin("LEAVE");
+        self.register_builtin("UNLOOP");
+        self.register_builtin("EXIT");
+    }
+
+    fn register_compiler_operations(&mut self) {
+        self.register_builtin("DO");
+        self.register_builtin("LOOP");
+        self.register_builtin("+LOOP");
+        self.register_builtin("BEGIN");
+        self.register_builtin("UNTIL");
+        self.register_builtin("WHILE");
+        self.register_builtin("REPEAT");
+        self.register_builtin("AGAIN");
+        self.register_builtin("DOES>");
+        self.register_builtin("IMMEDIATE");
+        self.register_builtin("POSTPONE");
+        self.register_builtin("[");
+        self.register_builtin("]");
+        self.register_builtin("LITERAL");
+        self.register_builtin("[']");
+        self.register_builtin("'");
+        self.register_builtin("EXECUTE");
+        self.register_builtin("RECURSE");
+        self.register_builtin("RECURSIVE");
+    }
+
+    fn register_exception_operations(&mut self) {
+        self.register_builtin("CATCH");
+        self.register_builtin("THROW");
+    }
+
+    fn register_memory_operations(&mut self) {
+        self.register_builtin("VARIABLE");
+        self.register_builtin("CONSTANT");
+        self.register_builtin("VALUE");
+        self.register_builtin("TO");
+        self.register_builtin("@");
+        self.register_builtin("!");
+        self.register_builtin("+!");
+        self.register_builtin("C@");
+        self.register_builtin("C!");
+        self.register_builtin("CREATE");
+        self.register_builtin("HERE");
+        self.register_builtin("ALLOT");
+        self.register_builtin(",");
+        self.register_builtin("C,");
+        self.register_builtin("ALIGN");
+        self.register_builtin("CELLS");
+        self.register_builtin("CELL+");
     }
 
     fn register_output_operations(&mut self) {
-        self.dict.insert(
-            "EMIT".to_string(),
-            Rc::new(Word::Builtin("EMIT".to_string())),
-        );
-        self.dict
-            .insert("CR".to_string(), Rc::new(Word::Builtin("CR".to_string())));
-        self.dict
-            .insert(".".to_string(), Rc::new(Word::Builtin(".".to_string())));
-        self.dict
-            .insert(".\"".to_string(), Rc::new(Word::Builtin(".\"".to_string())));
+        self.register_builtin("EMIT");
+        self.register_builtin("CR");
+        self.register_builtin(".");
+        self.register_builtin(".\"");
+    }
+
+    fn register_input_operations(&mut self) {
+        self.register_builtin("KEY");
+        self.register_builtin("KEY?");
+        self.register_builtin("ACCEPT");
     }
 
     /// Convierte el contenido de la pila en un vector.
-    pub fn stack_to_vec(&self) -> Vec<i16> {
+    pub fn stack_to_vec(&self) -> Vec<Cell> {
         self.stack.to_vec().to_vec()
     }
 
+    /// Devuelve la pila de datos.
+    pub fn stack(&self) -> &Stack {
+        &self.stack
+    }
+
+    /// Devuelve la pila de datos para modificarla, por ejemplo para pasar argumentos
+    /// a una word antes de ejecutarla.
+    pub fn stack_mut(&mut self) -> &mut Stack {
+        &mut self.stack
+    }
+
+    /// Devuelve la pila de retorno.
+    pub fn return_stack(&self) -> &Stack {
+        &self.return_stack
+    }
+
+    /// Busca una word en el diccionario, sin distinguir mayúsculas.
+    pub fn word(&self, name: &str) -> Option<Rc<Word>> {
+        self.dict
+            .get(&name.to_uppercase())
+            .map(|entry| Rc::clone(&entry.word))
+    }
+
+    /// Reemplaza el destino de salida, que por defecto es la salida estándar.
+    ///
+    /// Para capturar la salida en memoria puede usarse un `OutputBuffer`.
+    pub fn set_output<W: Write + 'static>(&mut self, output: W) {
+        self.output = Box::new(output);
+    }
+
+    /// Reemplaza la fuente de entrada, que por defecto es la entrada estándar.
+    pub fn set_input<R: BufRead + 'static>(&mut self, input: R) {
+        self.input = Box::new(input);
+    }
+
+    /// Escribe en el destino de salida del intérprete.
+    fn write_output(&mut self, args: fmt::Arguments) -> Result<(), ForthError> {
+        self.output
+            .write_fmt(args)
+            .map_err(|e| ForthError::Io(e.to_string()))
+    }
+
+    /// Registra una word implementada en Rust.
+    ///
+    /// La función recibe la pila de datos y el espacio de datos, y puede devolver un error
+    /// que se propaga igual que los de las words predefinidas (por ejemplo, `CATCH` lo captura).
+    /// Si ya existía una word con ese nombre, la nueva la reemplaza.
+    pub fn register_native<F>(&mut self, name: &str, f: F)
+    where
+        F: Fn(&mut Stack, &mut DataSpace) -> Result<(), ForthError> + 'static,
+    {
+        self.define(name.to_uppercase(), Word::Native(NativeWord::new(f)));
+    }
+
+    /// Devuelve los nombres de las words del diccionario, ordenados alfabéticamente.
+    pub fn word_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.dict.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
     /// Ejecuta una operación aritmética binaria.
     ///
     /// Este método extrae dos valores de la pila, aplica la operación y
-    /// empuja el resultado de vuelta a la pila.
-    fn apply_binary_op<F>(&mut self, op: F) -> Result<(), String>
+    /// empuja el resultado de vuelta a la pila. La operación se calcula sin
+    /// desbordes sobre `i128` y luego se ajusta al ancho de la celda.
+    fn apply_binary_op<F>(&mut self, op: F) -> Result<(), ForthError>
     where
-        F: Fn(i16, i16) -> i16,
+        F: Fn(i128, i128) -> i128,
     {
         let b = self.stack.pop()?;
         let a = self.stack.pop()?;
-        self.stack.push(op(a, b))
+        let result = self.arithmetic_result(op(i128::from(a), i128::from(b)))?;
+        self.stack.push(result)
+    }
+
+    /// Ajusta el resultado de una operación aritmética al ancho de la celda.
+    ///
+    /// Si no entra, da la vuelta o, con la aritmética verificada, produce un error.
+    fn arithmetic_result(&self, value: i128) -> Result<Cell, ForthError> {
+        let wrapped = self.cell.wrap(value as i64);
+        if self.checked_arithmetic && i128::from(wrapped) != value {
+            return Err(ForthError::ArithmeticOverflow);
+        }
+        Ok(wrapped)
     }
 
-    fn handle_swap(&mut self) -> Result<(), String> {
+    fn handle_swap(&mut self) -> Result<(), ForthError> {
         let b = self.stack.pop()?;
         let a = self.stack.pop()?;
         self.stack.push(b)?;
         self.stack.push(a)
     }
 
-    fn handle_division(&mut self) -> Result<(), String> {
-        let b = self.stack.pop()?;
-        if b == 0 {
-            return Err("division-by-zero".to_string());
+    /// Ejecuta una operación aritmética unaria sobre el tope de la pila.
+    fn apply_unary_op<F>(&mut self, op: F) -> Result<(), ForthError>
+    where
+        F: Fn(i128) -> i128,
+    {
+        let a = self.stack.pop()?;
+        let result = self.arithmetic_result(op(i128::from(a)))?;
+        self.stack.push(result)
+    }
+
+    /// Divide un dividendo de hasta dos celdas y devuelve `(resto, cociente)`.
+    ///
+    /// Con división simétrica el cociente se trunca hacia cero y el resto tiene el signo
+    /// del dividendo; con división con piso el cociente se redondea hacia menos infinito
+    /// y el resto tiene el signo del divisor.
+    fn divide(
+        &self,
+        dividend: i128,
+        divisor: Cell,
+        floored: bool,
+    ) -> Result<(Cell, Cell), ForthError> {
+        if divisor == 0 {
+            return Err(ForthError::DivisionByZero);
+        }
+        let divisor = i128::from(divisor);
+        // Solo desborda al dividir el mínimo de `i128` (un dividendo doble de 64 bits) por -1.
+        let mut quot = match dividend.checked_div(divisor) {
+            Some(quot) => quot,
+            None if self.checked_arithmetic => return Err(ForthError::ArithmeticOverflow),
+            None => dividend.wrapping_div(divisor),
+        };
+        let mut rem = dividend.checked_rem(divisor).unwrap_or(0);
+        if floored && rem != 0 && (rem < 0) != (divisor < 0) {
+            rem += divisor;
+            quot -= 1;
         }
+        Ok((self.arithmetic_result(rem)?, self.arithmetic_result(quot)?))
+    }
+
+    /// Saca un número de dos celdas de la pila: la celda más significativa está en el tope.
+    fn pop_double(&mut self) -> Result<i128, ForthError> {
+        let high = self.stack.pop()?;
+        let low = self.stack.pop()?;
+        Ok((i128::from(high) << self.cell.bits()) | i128::from(self.cell.to_unsigned(low)))
+    }
+
+    fn handle_division(&mut self) -> Result<(), ForthError> {
+        let b = self.stack.pop()?;
+        let a = self.stack.pop()?;
+        let (_, quot) = self.divide(i128::from(a), b, false)?;
+        self.stack.push(quot)
+    }
+
+    fn handle_mod(&mut self) -> Result<(), ForthError> {
+        let b = self.stack.pop()?;
+        let a = self.stack.pop()?;
+        let (rem, _) = self.divide(i128::from(a), b, false)?;
+        self.stack.push(rem)
+    }
+
+    fn handle_slash_mod(&mut self) -> Result<(), ForthError> {
+        let b = self.stack.pop()?;
+        let a = self.stack.pop()?;
+        let (rem, quot) = self.divide(i128::from(a), b, false)?;
+        self.stack.push(rem)?;
+        self.stack.push(quot)
+    }
+
+    /// Ejecuta `*/` y `*/MOD`: el producto intermedio se calcula en doble ancho.
+    fn handle_star_slash(&mut self, push_rem: bool) -> Result<(), ForthError> {
+        let c = self.stack.pop()?;
+        let b = self.stack.pop()?;
         let a = self.stack.pop()?;
-        self.stack.push(a / b)
+        let (rem, quot) = self.divide(i128::from(a) * i128::from(b), c, false)?;
+        if push_rem {
+            self.stack.push(rem)?;
+        }
+        self.stack.push(quot)
     }
 
-    fn handle_dup(&mut self) -> Result<(), String> {
+    /// Ejecuta `FM/MOD` (con piso) y `SM/REM` (simétrica) sobre un dividendo de dos celdas.
+    fn handle_double_division(&mut self, floored: bool) -> Result<(), ForthError> {
+        let divisor = self.stack.pop()?;
+        let dividend = self.pop_double()?;
+        let (rem, quot) = self.divide(dividend, divisor, floored)?;
+        self.stack.push(rem)?;
+        self.stack.push(quot)
+    }
+
+    fn handle_dup(&mut self) -> Result<(), ForthError> {
         let val = self.stack.peek()?;
         self.stack.push(val)
     }
 
-    fn handle_drop(&mut self) -> Result<(), String> {
-        self.stack.pop().map(|_| ()).map_err(|e| e.to_string())
+    fn handle_drop(&mut self) -> Result<(), ForthError> {
+        self.stack.pop().map(|_| ())
     }
 
-    fn handle_rot(&mut self) -> Result<(), String> {
+    fn handle_rot(&mut self) -> Result<(), ForthError> {
         let c = self.stack.pop()?;
         let b = self.stack.pop()?;
         let a = self.stack.pop()?;
@@ -169,41 +596,278 @@ impl Interpreter {
         self.stack.push(a)
     }
 
-    fn handle_not(&mut self) -> Result<(), String> {
+    /// Empuja un valor en la pila de retorno.
+    fn push_return(&mut self, value: Cell) -> Result<(), ForthError> {
+        self.return_stack
+            .push(value)
+            .map_err(|_| ForthError::ReturnStackOverflow)
+    }
+
+    /// Saca el valor superior de la pila de retorno.
+    fn pop_return(&mut self) -> Result<Cell, ForthError> {
+        self.return_stack
+            .pop()
+            .map_err(|_| ForthError::ReturnStackUnderflow)
+    }
+
+    fn handle_to_r(&mut self) -> Result<(), ForthError> {
+        let val = self.stack.pop()?;
+        self.push_return(val)
+    }
+
+    fn handle_r_from(&mut self) -> Result<(), ForthError> {
+        let val = self.pop_return()?;
+        self.stack.push(val)
+    }
+
+    fn handle_r_fetch(&mut self) -> Result<(), ForthError> {
+        let val = self
+            .return_stack
+            .peek()
+            .map_err(|_| ForthError::ReturnStackUnderflow)?;
+        self.stack.push(val)
+    }
+
+    fn handle_two_to_r(&mut self) -> Result<(), ForthError> {
+        let b = self.stack.pop()?;
+        let a = self.stack.pop()?;
+        self.push_return(a)?;
+        self.push_return(b)
+    }
+
+    fn handle_two_r_from(&mut self) -> Result<(), ForthError> {
+        let b = self.pop_return()?;
+        let a = self.pop_return()?;
+        self.stack.push(a)?;
+        self.stack.push(b)
+    }
+
+    /// Copia los dos valores superiores de la pila de retorno sin sacarlos.
+    fn handle_two_r_fetch(&mut self) -> Result<(), ForthError> {
+        let underflow = |_| ForthError::ReturnStackUnderflow;
+        let a = self.return_stack.pick(1).map_err(underflow)?;
+        let b = self.return_stack.peek().map_err(underflow)?;
+        self.stack.push(a)?;
+        self.stack.push(b)
+    }
+
+    fn handle_not(&mut self) -> Result<(), ForthError> {
         let a = self.stack.pop()?;
         let result = if a == 0 { -1 } else { 0 };
         self.stack.push(result)
     }
 
-    fn handle_emit(&mut self) -> Result<(), String> {
+    fn handle_emit(&mut self) -> Result<(), ForthError> {
         let code = self.stack.pop()?;
-        let c = std::char::from_u32(code as u32)
-            .ok_or_else(|| "Valor para EMIT no es un carácter válido".to_string())?;
-        print!("{} ", c);
-        Ok(())
+        let c = std::char::from_u32(code as u32).ok_or(ForthError::InvalidCharacter)?;
+        self.write_output(format_args!("{} ", c))
+    }
+
+    /// Vacía el destino de salida, para que lo impreso (por ejemplo, un mensaje que pide
+    /// una tecla) se vea antes de esperar la entrada.
+    fn flush_output(&mut self) -> Result<(), ForthError> {
+        self.output
+            .flush()
+            .map_err(|e| ForthError::Io(e.to_string()))
+    }
+
+    /// Lee un byte de la fuente de entrada. Devuelve `None` si la entrada se terminó.
+    fn read_input_byte(&mut self) -> Result<Option<u8>, ForthError> {
+        let io_error = |e: io::Error| ForthError::Io(e.to_string());
+        let byte = self.input.fill_buf().map_err(io_error)?.first().copied();
+        if byte.is_some() {
+            self.input.consume(1);
+        }
+        Ok(byte)
+    }
+
+    /// Empuja el siguiente carácter de la entrada, o -1 si la entrada se terminó.
+    fn handle_key(&mut self) -> Result<(), ForthError> {
+        self.flush_output()?;
+        let key = self.read_input_byte()?.map_or(-1, Cell::from);
+        self.stack.push(key)
+    }
+
+    /// Empuja verdadero si hay un carácter disponible para `KEY`.
+    ///
+    /// Para saberlo se intenta leer de la fuente de entrada, por lo que si todavía no hay
+    /// nada para leer (por ejemplo, en una terminal) se espera hasta que llegue un carácter
+    /// o se termine la entrada.
+    fn handle_key_question(&mut self) -> Result<(), ForthError> {
+        self.flush_output()?;
+        let available = !self
+            .input
+            .fill_buf()
+            .map_err(|e| ForthError::Io(e.to_string()))?
+            .is_empty();
+        self.stack.push(if available { -1 } else { 0 })
+    }
+
+    /// Lee una línea de la entrada, de a lo sumo `n` caracteres, y la guarda en `addr`.
+    ///
+    /// El fin de línea no se guarda. Empuja la cantidad de caracteres leídos.
+    fn handle_accept(&mut self) -> Result<(), ForthError> {
+        let max = self.stack.pop()?.max(0) as usize;
+        let addr = self.data_space.address(self.stack.pop()?);
+        self.flush_output()?;
+        let mut count = 0;
+        while count < max {
+            match self.read_input_byte()? {
+                None | Some(b'\n') => break,
+                Some(b'\r') => {}
+                Some(byte) => {
+                    self.data_space.store_byte(addr + count, byte)?;
+                    count += 1;
+                }
+            }
+        }
+        self.stack.push(count as Cell)
+    }
+
+    /// Saca el operando de una operación de bits: en modo de flags, se convierte en el
+    /// flag (-1 o 0) que corresponde al valor.
+    fn pop_bitwise_operand(&mut self) -> Result<Cell, ForthError> {
+        let value = self.stack.pop()?;
+        Ok(match (self.logical_flags, value) {
+            (false, value) => value,
+            (true, 0) => 0,
+            (true, _) => -1,
+        })
     }
 
-    fn handle_and(&mut self) -> Result<(), String> {
-        self.apply_binary_op(|a, b| if a != 0 && b != 0 { -1 } else { 0 })
+    /// Ejecuta una operación de bits sobre los dos valores superiores de la pila.
+    fn apply_bitwise_op<F>(&mut self, op: F) -> Result<(), ForthError>
+    where
+        F: Fn(Cell, Cell) -> Cell,
+    {
+        let b = self.pop_bitwise_operand()?;
+        let a = self.pop_bitwise_operand()?;
+        self.stack.push(op(a, b))
+    }
+
+    fn handle_invert(&mut self) -> Result<(), ForthError> {
+        let a = self.pop_bitwise_operand()?;
+        self.stack.push(!a)
     }
 
-    fn handle_or(&mut self) -> Result<(), String> {
-        self.apply_binary_op(|a, b| if a != 0 || b != 0 { -1 } else { 0 })
+    /// Ejecuta `LSHIFT` o `RSHIFT`: desplaza los bits de la celda sin propagar el signo.
+    ///
+    /// Desplazar en la cantidad de bits de la celda o más da cero.
+    fn handle_shift(&mut self, left: bool) -> Result<(), ForthError> {
+        let count = self.cell.to_unsigned(self.stack.pop()?);
+        let bits = self.cell.to_unsigned(self.stack.pop()?);
+        if count >= u64::from(self.cell.bits()) {
+            return self.stack.push(0);
+        }
+        let shifted = if left { bits << count } else { bits >> count };
+        self.stack.push(self.cell.wrap(shifted as i64))
     }
 
-    fn handle_equals(&mut self) -> Result<(), String> {
+    fn handle_equals(&mut self) -> Result<(), ForthError> {
         self.apply_binary_op(|a, b| if a == b { -1 } else { 0 })
     }
 
-    fn handle_less_than(&mut self) -> Result<(), String> {
+    fn handle_less_than(&mut self) -> Result<(), ForthError> {
         self.apply_binary_op(|a, b| if a < b { -1 } else { 0 })
     }
 
-    fn handle_greater_than(&mut self) -> Result<(), String> {
+    fn handle_greater_than(&mut self) -> Result<(), ForthError> {
         self.apply_binary_op(|a, b| if a > b { -1 } else { 0 })
     }
 
-    fn handle_over(&mut self) -> Result<(), String> {
+    /// Ejecuta `U<` o `U>`, que comparan las celdas como números sin signo de su ancho.
+    fn handle_unsigned_comparison(&mut self, less_than: bool) -> Result<(), ForthError> {
+        let b = self.cell.to_unsigned(self.stack.pop()?);
+        let a = self.cell.to_unsigned(self.stack.pop()?);
+        let result = if less_than { a < b } else { a > b };
+        self.stack.push(if result { -1 } else { 0 })
+    }
+
+    /// Ejecuta `WITHIN ( n lo hi -- flag )`: verdadero si `lo <= n < hi`.
+    ///
+    /// Como en Forth estándar, se calcula como `(n - lo) U< (hi - lo)`, por lo que vale
+    /// tanto para números con signo como sin signo y el rango puede dar la vuelta.
+    fn handle_within(&mut self) -> Result<(), ForthError> {
+        let hi = self.stack.pop()?;
+        let lo = self.stack.pop()?;
+        let n = self.stack.pop()?;
+        let offset = self.cell.to_unsigned(n.wrapping_sub(lo));
+        let range = self.cell.to_unsigned(hi.wrapping_sub(lo));
+        self.stack.push(if offset < range { -1 } else { 0 })
+    }
+
+    /// Saca del tope de la pila la cantidad de elementos que usan `PICK` y `ROLL`.
+    fn pop_stack_index(&mut self) -> Result<usize, ForthError> {
+        let n = self.stack.pop()?;
+        Ok(usize::try_from(self.cell.to_unsigned(n)).unwrap_or(usize::MAX))
+    }
+
+    fn handle_pick(&mut self) -> Result<(), ForthError> {
+        let n = self.pop_stack_index()?;
+        let value = self.stack.pick(n)?;
+        self.stack.push(value)
+    }
+
+    fn handle_roll(&mut self) -> Result<(), ForthError> {
+        let n = self.pop_stack_index()?;
+        self.stack.roll(n)
+    }
+
+    fn handle_nip(&mut self) -> Result<(), ForthError> {
+        let b = self.stack.pop()?;
+        self.stack.pop()?;
+        self.stack.push(b)
+    }
+
+    fn handle_tuck(&mut self) -> Result<(), ForthError> {
+        let b = self.stack.pop()?;
+        let a = self.stack.pop()?;
+        self.stack.push(b)?;
+        self.stack.push(a)?;
+        self.stack.push(b)
+    }
+
+    fn handle_minus_rot(&mut self) -> Result<(), ForthError> {
+        self.stack.roll(2)?;
+        self.stack.roll(2)
+    }
+
+    fn handle_question_dup(&mut self) -> Result<(), ForthError> {
+        let val = self.stack.peek()?;
+        if val != 0 {
+            self.stack.push(val)?;
+        }
+        Ok(())
+    }
+
+    fn handle_two_dup(&mut self) -> Result<(), ForthError> {
+        let a = self.stack.pick(1)?;
+        let b = self.stack.peek()?;
+        self.stack.push(a)?;
+        self.stack.push(b)
+    }
+
+    fn handle_two_drop(&mut self) -> Result<(), ForthError> {
+        if self.stack.depth() < 2 {
+            return Err(ForthError::StackUnderflow);
+        }
+        self.stack.truncate(self.stack.depth() - 2);
+        Ok(())
+    }
+
+    fn handle_two_swap(&mut self) -> Result<(), ForthError> {
+        self.stack.roll(3)?;
+        self.stack.roll(3)
+    }
+
+    fn handle_two_over(&mut self) -> Result<(), ForthError> {
+        let a = self.stack.pick(3)?;
+        let b = self.stack.pick(2)?;
+        self.stack.push(a)?;
+        self.stack.push(b)
+    }
+
+    fn handle_over(&mut self) -> Result<(), ForthError> {
         let b = self.stack.pop()?;
         let a = self.stack.pop()?;
         self.stack.push(a)?;
@@ -211,7 +875,7 @@ impl Interpreter {
         self.stack.push(a)
     }
 
-    fn handle_if(&mut self) -> Result<(), String> {
+    fn handle_if(&mut self) -> Result<(), ForthError> {
         let is_nested = self.stack.to_vec().len() > 1;
         let condition = self.stack.pop()?;
         if is_nested {
@@ -235,7 +899,7 @@ impl Interpreter {
         Ok(())
     }
 
-    fn handle_else(&mut self) -> Result<(), String> {
+    fn handle_else(&mut self) -> Result<(), ForthError> {
         let mut nesting = 1;
         while let Some(token) = self.next_token() {
             if token == "IF" {
@@ -250,92 +914,469 @@ impl Interpreter {
         Ok(())
     }
 
-    fn handle_then(&mut self) -> Result<(), String> {
-        if let Some(cond) = self.saved_cond.take() {
-            if cond != 0 {
-                self.stack.push(cond)?;
-            }
+    fn handle_then(&mut self) -> Result<(), ForthError> {
+        if let Some(cond) = self.saved_cond.take()
+            && cond != 0
+        {
+            self.stack.push(cond)?;
         }
         Ok(())
     }
 
-    fn handle_dot_quote(&mut self) -> Result<(), String> {
-        if let Some(literal) = self.next_token() {
-            if self.last_was_dot_quote {
-                print!(" ");
-            }
-            let output = literal.trim_start();
-            print!("{}", output);
-            self.last_was_dot_quote = true;
-            Ok(())
-        } else {
-            Err("Missing closing quote for .\"".to_string())
+    /// Empuja el índice del bucle contado ubicado `depth` niveles por debajo del más interno.
+    fn handle_loop_index(&mut self, depth: usize) -> Result<(), ForthError> {
+        let len = self.loop_stack.len();
+        if len <= depth {
+            return Err(ForthError::LoopStackUnderflow);
         }
+        let (index, _) = self.loop_stack[len - 1 - depth];
+        self.stack.push(index)
     }
 
-    /// Obtiene el siguiente token.
-    fn next_token(&mut self) -> Option<String> {
-        if self.token_index < self.tokens.len() {
-            let token = self.tokens[self.token_index].clone();
-            self.token_index += 1;
-            Some(token)
-        } else {
-            None
-        }
+    fn handle_unloop(&mut self) -> Result<(), ForthError> {
+        self.loop_stack
+            .pop()
+            .map(|_| ())
+            .ok_or(ForthError::LoopStackUnderflow)
     }
 
-    /// Lee un literal encerrado entre comillas a partir de la posición indicada.
-    fn read_quoted_literal(chars: &[char], i: &mut usize) -> String {
-        let start = *i;
-        while *i < chars.len() && chars[*i] != '"' {
-            *i += 1;
-        }
-        let literal: String = chars[start..*i].iter().collect();
-        if *i < chars.len() {
-            *i += 1;
-        }
-        literal
+    /// Devuelve el ancho de las celdas del intérprete.
+    pub fn cell_width(&self) -> CellWidth {
+        self.cell
     }
 
-    /// Divide la línea en tokens.
-    fn tokenize(line: &str) -> Vec<String> {
-        let mut tokens = Vec::new();
-        let chars: Vec<char> = line.chars().collect();
-        let mut i = 0;
+    /// Lee el nombre de la word a definir a continuación del token actual.
+    fn read_word_name(&mut self) -> Result<String, ForthError> {
+        let name = self.next_token().ok_or(ForthError::InvalidWord)?;
+        if self.cell.parse(&name).is_some() {
+            return Err(ForthError::InvalidWord);
+        }
+        Ok(name.to_uppercase())
+    }
 
-        while i < chars.len() {
-            if chars[i].is_whitespace() {
-                i += 1;
-                continue;
-            }
-            if i + 1 < chars.len() && chars[i] == '.' && chars[i + 1] == '"' {
-                tokens.push(".\"".to_string());
-                i += 2;
-                let literal = Interpreter::read_quoted_literal(&chars, &mut i);
-                tokens.push(literal);
-            } else {
-                let start = i;
-                while i < chars.len() && !chars[i].is_whitespace() {
-                    i += 1;
-                }
-                let token: String = chars[start..i].iter().collect();
-                tokens.push(token);
-            }
+    /// Lee el nombre de un VALUE a continuación del token actual y devuelve su dirección.
+    fn read_value_address(&mut self) -> Result<usize, ForthError> {
+        let name = self.next_token().ok_or(ForthError::InvalidWord)?;
+        match self
+            .dict
+            .get(&name.to_uppercase())
+            .map(|entry| &*entry.word)
+        {
+            Some(Word::Value(addr)) => Ok(*addr),
+            _ => Err(ForthError::InvalidWord),
         }
-        tokens
     }
 
-    /// Procesa una línea de entrada en el lenguaje Forth.
-    ///
-    /// Este método divide la línea en tokens, los resuelve y los ejecuta.
-    pub fn parse_line(&mut self, line: &str) -> Result<(), String> {
-        self.tokens = Interpreter::tokenize(line);
-        self.token_index = 0;
+    fn handle_variable(&mut self) -> Result<(), ForthError> {
+        let name = self.read_word_name()?;
+        self.data_space.align()?;
+        let addr = self.data_space.allot(self.data_space.cell_size())?;
+        self.define(name, Word::DataField(addr));
+        Ok(())
+    }
 
+    fn handle_create(&mut self) -> Result<(), ForthError> {
+        let name = self.read_word_name()?;
+        self.data_space.align()?;
+        let addr = self.data_space.here();
+        self.define(name.clone(), Word::DataField(addr));
+        self.last_created = Some((name, addr));
+        Ok(())
+    }
+
+    /// Asigna el cuerpo de `DOES>` a la última word creada y termina la word definidora.
+    fn run_does(&mut self, body: &Rc<Word>) -> Result<(), ForthError> {
+        let (name, addr) = self.last_created.clone().ok_or(ForthError::InvalidWord)?;
+        let entry = self.dict.get_mut(&name).ok_or(ForthError::InvalidWord)?;
+        entry.word = Rc::new(Word::DataFieldDoes {
+            addr,
+            body: Rc::clone(body),
+        });
+        self.pending = Some(ControlSignal::Exit);
+        Ok(())
+    }
+
+    fn handle_allot(&mut self) -> Result<(), ForthError> {
+        let n = self.stack.pop()?;
+        if n < 0 {
+            self.data_space.release(n.unsigned_abs() as usize)
+        } else {
+            self.data_space.allot(n as usize).map(|_| ())
+        }
+    }
+
+    fn handle_comma(&mut self) -> Result<(), ForthError> {
+        let value = self.stack.pop()?;
+        let addr = self.data_space.allot(self.data_space.cell_size())?;
+        self.data_space.store(addr, value)
+    }
+
+    fn handle_c_comma(&mut self) -> Result<(), ForthError> {
+        let value = self.stack.pop()?;
+        let addr = self.data_space.allot(1)?;
+        self.data_space.store_byte(addr, value as u8)
+    }
+
+    fn handle_constant(&mut self) -> Result<(), ForthError> {
+        let name = self.read_word_name()?;
+        let value = self.stack.pop()?;
+        self.define(name, Word::Number(value));
+        Ok(())
+    }
+
+    fn handle_value(&mut self) -> Result<(), ForthError> {
+        let name = self.read_word_name()?;
+        let value = self.stack.pop()?;
+        self.data_space.align()?;
+        let addr = self.data_space.allot(self.data_space.cell_size())?;
+        self.data_space.store(addr, value)?;
+        self.define(name, Word::Value(addr));
+        Ok(())
+    }
+
+    fn handle_to(&mut self) -> Result<(), ForthError> {
+        let addr = self.read_value_address()?;
+        let value = self.stack.pop()?;
+        self.data_space.store(addr, value)
+    }
+
+    fn handle_fetch(&mut self) -> Result<(), ForthError> {
+        let addr = self.data_space.address(self.stack.pop()?);
+        let value = self.data_space.fetch(addr)?;
+        self.stack.push(value)
+    }
+
+    fn handle_store(&mut self) -> Result<(), ForthError> {
+        let addr = self.data_space.address(self.stack.pop()?);
+        let value = self.stack.pop()?;
+        self.data_space.store(addr, value)
+    }
+
+    fn handle_plus_store(&mut self) -> Result<(), ForthError> {
+        let addr = self.data_space.address(self.stack.pop()?);
+        let value = self.stack.pop()?;
+        let current = self.data_space.fetch(addr)?;
+        self.data_space
+            .store(addr, self.cell.wrap(current.wrapping_add(value)))
+    }
+
+    fn handle_c_fetch(&mut self) -> Result<(), ForthError> {
+        let addr = self.data_space.address(self.stack.pop()?);
+        let value = self.data_space.fetch_byte(addr)?;
+        self.stack.push(Cell::from(value))
+    }
+
+    fn handle_c_store(&mut self) -> Result<(), ForthError> {
+        let addr = self.data_space.address(self.stack.pop()?);
+        let value = self.stack.pop()?;
+        self.data_space.store_byte(addr, value as u8)
+    }
+
+    /// Agrega una word al diccionario y la registra como la última definida.
+    ///
+    /// La nueva entrada no es inmediata aunque la definición anterior lo fuera.
+    fn define(&mut self, name: String, word: Word) -> Rc<Word> {
+        let word = Rc::new(word);
+        self.last_defined = Some(name.clone());
+        let entry = Entry {
+            word: Rc::clone(&word),
+            immediate: false,
+        };
+        self.dict.insert(name, entry);
+        word
+    }
+
+    fn handle_immediate(&mut self) -> Result<(), ForthError> {
+        let entry = self
+            .last_defined
+            .as_ref()
+            .and_then(|name| self.dict.get_mut(name))
+            .ok_or(ForthError::InvalidWord)?;
+        entry.immediate = true;
+        Ok(())
+    }
+
+    /// Devuelve el execution token de una word, asignándole uno nuevo si aún no lo tiene.
+    fn execution_token(&mut self, word: &Rc<Word>) -> Cell {
+        if let Some(index) = self.xts.iter().position(|w| Rc::ptr_eq(w, word)) {
+            return index as Cell;
+        }
+        self.xts.push(Rc::clone(word));
+        (self.xts.len() - 1) as Cell
+    }
+
+    /// Lee el nombre de una word a continuación del token actual y devuelve su execution token.
+    fn read_execution_token(&mut self) -> Result<Cell, ForthError> {
+        let name = self.next_token().ok_or(ForthError::InvalidWord)?;
+        let word = self
+            .dict
+            .get(&name.to_uppercase())
+            .map(|entry| Rc::clone(&entry.word))
+            .ok_or(ForthError::UnknownWord(name))?;
+        Ok(self.execution_token(&word))
+    }
+
+    fn handle_tick(&mut self) -> Result<(), ForthError> {
+        let xt = self.read_execution_token()?;
+        self.stack.push(xt)
+    }
+
+    fn handle_execute(&mut self) -> Result<(), ForthError> {
+        let xt = self.stack.pop()?;
+        self.run_execution_token(xt)
+    }
+
+    /// Ejecuta la word asociada a un execution token.
+    fn run_execution_token(&mut self, xt: Cell) -> Result<(), ForthError> {
+        let word = usize::try_from(xt)
+            .ok()
+            .and_then(|index| self.xts.get(index))
+            .cloned()
+            .ok_or(ForthError::InvalidWord)?;
+        self.run_word(&word)
+    }
+
+    /// Ejecuta el execution token del tope de la pila capturando cualquier error.
+    ///
+    /// Si la ejecución termina bien se empuja 0. Si falla, se restauran la pila de datos,
+    /// la pila de retorno y la de bucles al estado previo y se empuja el código de excepción.
+    fn handle_catch(&mut self) -> Result<(), ForthError> {
+        let xt = self.stack.pop()?;
+        let saved_stack = self.stack.to_vec().to_vec();
+        let saved_return_stack = self.return_stack.to_vec().to_vec();
+        let loop_depth = self.loop_stack.len();
+        let postponed_len = self.postponed.len();
+
+        match self.run_execution_token(xt) {
+            Ok(()) => self.stack.push(0),
+            Err(e) => {
+                Interpreter::restore_stack(&mut self.stack, &saved_stack)?;
+                Interpreter::restore_stack(&mut self.return_stack, &saved_return_stack)?;
+                self.loop_stack.truncate(loop_depth);
+                self.pending = None;
+                self.postponed.truncate(postponed_len);
+                self.postponed_terminator = None;
+                self.backtrace = None;
+                self.error_token = None;
+                self.stack.push(e.code())
+            }
+        }
+    }
+
+    /// Devuelve una pila al contenido guardado antes de un `CATCH`.
+    fn restore_stack(stack: &mut Stack, saved: &[Cell]) -> Result<(), ForthError> {
+        stack.truncate(0);
+        for &value in saved {
+            stack.push(value)?;
+        }
+        Ok(())
+    }
+
+    fn handle_throw(&mut self) -> Result<(), ForthError> {
+        let code = self.stack.pop()?;
+        if code == 0 {
+            return Ok(());
+        }
+        Err(ForthError::from_code(code))
+    }
+
+    fn handle_dot_quote(&mut self) -> Result<(), ForthError> {
+        if let Some(literal) = self.next_token() {
+            if self.last_was_dot_quote {
+                self.write_output(format_args!(" "))?;
+            }
+            let output = literal.trim_start();
+            self.write_output(format_args!("{}", output))?;
+            self.last_was_dot_quote = true;
+            Ok(())
+        } else {
+            Err(ForthError::UnterminatedString)
+        }
+    }
+
+    /// Obtiene el siguiente token.
+    fn next_token(&mut self) -> Option<String> {
+        if self.token_index < self.tokens.len() {
+            let token = self.tokens[self.token_index].text.clone();
+            self.token_index += 1;
+            Some(token)
+        } else {
+            None
+        }
+    }
+
+    /// Lee un literal encerrado entre comillas a partir de la posición indicada.
+    fn read_quoted_literal(chars: &[char], i: &mut usize) -> String {
+        let start = *i;
+        while *i < chars.len() && chars[*i] != '"' {
+            *i += 1;
+        }
+        let literal: String = chars[start..*i].iter().collect();
+        if *i < chars.len() {
+            *i += 1;
+        }
+        literal
+    }
+
+    /// Divide la línea en tokens, registrando la posición de cada uno.
+    fn tokenize(line: &str) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let chars: Vec<char> = line.chars().collect();
+        let spans = Interpreter::char_spans(&chars);
+        let mut i = 0;
+
+        while i < chars.len() {
+            if chars[i].is_whitespace() {
+                i += 1;
+                continue;
+            }
+            if i + 1 < chars.len() && chars[i] == '.' && chars[i + 1] == '"' {
+                tokens.push(Token {
+                    text: ".\"".to_string(),
+                    span: spans[i],
+                });
+                i += 2;
+                let span = spans.get(i).copied().unwrap_or(spans[i - 2]);
+                let text = Interpreter::read_quoted_literal(&chars, &mut i);
+                tokens.push(Token { text, span });
+            } else {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                tokens.push(Token {
+                    text,
+                    span: spans[start],
+                });
+            }
+        }
+        tokens
+    }
+
+    /// Indica si el código deja abierta una definición, es decir, un `:` sin su `;`.
+    pub fn is_incomplete(source: &str) -> bool {
+        let mut open = false;
+        for token in Interpreter::tokenize(source) {
+            match token.text.as_str() {
+                ":" => open = true,
+                ";" => open = false,
+                _ => {}
+            }
+        }
+        open
+    }
+
+    /// Calcula la línea y la columna de cada carácter de la entrada.
+    fn char_spans(chars: &[char]) -> Vec<Span> {
+        let mut spans = Vec::with_capacity(chars.len());
+        let mut span = Span { line: 1, column: 1 };
+        for &c in chars {
+            spans.push(span);
+            if c == '\n' {
+                span.line += 1;
+                span.column = 1;
+            } else {
+                span.column += 1;
+            }
+        }
+        spans
+    }
+
+    /// Procesa código fuente en el lenguaje Forth.
+    ///
+    /// Este método divide el código en tokens, los resuelve y los ejecuta.
+    /// El diccionario y las pilas se conservan entre llamadas.
+    pub fn eval(&mut self, source: &str) -> Result<(), ForthError> {
+        self.parse_source(source).map_err(|e| e.error)
+    }
+
+    /// Ejecuta un programa completo del mismo modo que el binario ejecuta un archivo.
+    ///
+    /// Ante un error, su mensaje se imprime en el destino de salida y se descarta el estado
+    /// de ejecución, incluida la pila; solo si el error es un desborde de la pila de datos
+    /// se conserva su contenido. El error se devuelve igual que en `parse_source`.
+    pub fn run_program(&mut self, source: &str) -> Result<(), SourceError> {
+        let result = self.parse_source(source);
+        if let Err(e) = &result {
+            let _ = self.write_output(format_args!("{}", e));
+            let stack = self.stack.to_vec().to_vec();
+            self.abort();
+            if e.error == ForthError::StackOverflow {
+                let _ = Interpreter::restore_stack(&mut self.stack, &stack);
+            }
+        }
+        result
+    }
+
+    /// Procesa código fuente Forth igual que `eval`, pero ante un error indica
+    /// la posición y el token en el que se produjo y las words de usuario en ejecución.
+    pub fn parse_source(&mut self, source: &str) -> Result<(), SourceError> {
+        self.tokens = Interpreter::tokenize(source);
+        self.token_index = 0;
+        self.backtrace = None;
+        self.error_token = None;
+
+        self.run_tokens().map_err(|error| {
+            let token = self
+                .error_token
+                .take()
+                .unwrap_or_else(|| self.current_token());
+            let backtrace = self.backtrace.take().unwrap_or_default();
+            SourceError {
+                error,
+                span: token.span,
+                token: token.text,
+                backtrace: self.describe_backtrace(&backtrace),
+            }
+        })
+    }
+
+    /// Devuelve el último token leído de la entrada.
+    fn current_token(&self) -> Token {
+        self.token_index
+            .checked_sub(1)
+            .and_then(|i| self.tokens.get(i))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Nombra las definiciones de la cadena de llamadas, de la más interna a la más externa.
+    ///
+    /// Las llamadas consecutivas a una misma definición, como en una recursión, se agrupan.
+    fn describe_backtrace(&self, backtrace: &[Rc<Word>]) -> Vec<String> {
+        let mut frames: Vec<(String, usize)> = Vec::new();
+        for word in backtrace.iter().rev() {
+            let name = self.word_name(word);
+            match frames.last_mut() {
+                Some((last, count)) if *last == name => *count += 1,
+                _ => frames.push((name, 1)),
+            }
+        }
+        frames
+            .into_iter()
+            .map(|(name, count)| match count {
+                1 => name,
+                _ => format!("{} ({} veces)", name, count),
+            })
+            .collect()
+    }
+
+    /// Busca el nombre con el que una definición figura en el diccionario.
+    fn word_name(&self, word: &Rc<Word>) -> String {
+        self.dict
+            .iter()
+            .find(|(_, entry)| Rc::ptr_eq(&entry.word, word))
+            .map(|(name, _)| name.clone())
+            .unwrap_or_else(|| "(sin nombre)".to_string())
+    }
+
+    /// Procesa los tokens pendientes de la entrada.
+    fn run_tokens(&mut self) -> Result<(), ForthError> {
         while let Some(token) = self.next_token() {
             match token.as_str() {
                 ":" => {
-                    let name = self.next_token().ok_or("invalid-word".to_string())?;
+                    let name = self.next_token().ok_or(ForthError::InvalidWord)?;
                     self.start_definition(&name)?;
                 }
                 ";" => {
@@ -351,114 +1392,174 @@ impl Interpreter {
     }
 
     /// Inicia la definición de una nueva word.
-    fn start_definition(&mut self, name: &str) -> Result<(), String> {
+    fn start_definition(&mut self, name: &str) -> Result<(), ForthError> {
         if self.compiling.is_some() {
-            return Err("Syntax error: nested definitions are not allowed".to_string());
+            return Err(ForthError::NestedDefinition);
         }
 
-        if name.parse::<i16>().is_ok() {
-            return Err("invalid-word".to_string());
+        if self.cell.parse(name).is_some() {
+            return Err(ForthError::InvalidWord);
         }
 
         let name_upper = name.to_uppercase();
-        self.compiling = Some((name_upper, Vec::new()));
+        self.compiling = Some((name_upper, Body::default()));
+        self.does_index = None;
+        self.self_reference = SelfReference::default();
+        self.recursive = false;
         Ok(())
     }
 
     /// Finaliza la definición en curso y la agrega al diccionario.
-    fn end_definition(&mut self) -> Result<(), String> {
-        if let Some((name, words)) = self.compiling.take() {
-            self.dict.insert(name, Rc::new(Word::Words(words)));
+    ///
+    /// Si la definición contiene `DOES>`, las words que lo siguen se agrupan en un
+    /// cuerpo compartido que se asignará a cada word creada por la word definidora.
+    fn end_definition(&mut self) -> Result<(), ForthError> {
+        if let Some((name, mut words)) = self.compiling.take() {
+            if let Some((index, token)) = self.does_index.take() {
+                let body = words.split_off(index);
+                words.push(Rc::new(Word::Does(Rc::new(Word::Words(body)))), token);
+            }
+            let word = self.define(name, Word::Words(words));
+            self.self_reference.resolve(&word);
             Ok(())
         } else {
-            Err("invalid-word".to_string())
+            Err(ForthError::InvalidWord)
         }
     }
 
+    /// Marca el comienzo del cuerpo `DOES>` dentro de la definición en curso.
+    fn mark_does(&mut self) -> Result<(), ForthError> {
+        if self.does_index.is_some() {
+            return Err(ForthError::UnexpectedToken);
+        }
+        if let Some((_, ref words)) = self.compiling {
+            self.does_index = Some((words.len(), self.current_token()));
+        }
+        Ok(())
+    }
+
     /// Procesa un token, ya sea ejecutándolo o compilándolo.
-    fn process_token(&mut self, token: &str) -> Result<(), String> {
-        if self.compiling.is_some() {
-            let token_upper = token.to_uppercase();
-            if token_upper == ".\"" {
-                let literal = self.next_token().ok_or("Missing closing quote for .\"")?;
-                let literal = literal.trim_start().to_owned();
-                if let Some((_, ref mut words)) = self.compiling {
-                    words.push(Rc::new(Word::StringLiteral(literal)));
-                }
-                return Ok(());
-            } else if token_upper == "IF" {
-                return self.compile_if();
-            } else if token_upper == "ELSE" || token_upper == "THEN" {
-                return Err("Unexpected token in definition".to_string());
-            }
+    fn process_token(&mut self, token: &str) -> Result<(), ForthError> {
+        let token_upper = token.to_uppercase();
+        if self.compiling.is_some() && token_upper == "IF" {
+            return self.compile_if();
+        }
+        if self.compiling.is_some() && token_upper == "DOES>" {
+            return self.mark_does();
         }
 
-        let word = self.resolve_token(token)?;
-        if let Some((_, ref mut words)) = self.compiling {
-            words.push(word);
+        let words = if self.compiling.is_some() || matches!(token_upper.as_str(), "DO" | "BEGIN") {
+            let words = self.compile_token(token)?;
+            if self.postponed_terminator.take().is_some() {
+                return Err(ForthError::UnexpectedToken);
+            }
+            words
+        } else {
+            let mut words = Body::default();
+            words.push(self.resolve_token(token)?, self.current_token());
+            words
+        };
+        if let Some((_, ref mut body)) = self.compiling {
+            body.extend(words);
         } else {
-            self.run_word(&word)?;
+            for word in words.words() {
+                self.execute_word(word)?;
+            }
         }
         Ok(())
     }
 
+    /// Ejecuta una word fuera de la compilación.
+    fn execute_word(&mut self, word: &Rc<Word>) -> Result<(), ForthError> {
+        let result = self.run_word(word);
+        self.pending = None;
+        result
+    }
+
+    /// Obtiene el siguiente token de una estructura en compilación.
+    ///
+    /// Si una word inmediata cerró la estructura con `POSTPONE`, ese cierre se devuelve
+    /// antes de seguir leyendo la entrada.
+    fn next_compile_token(&mut self) -> Option<String> {
+        self.postponed_terminator
+            .take()
+            .or_else(|| self.next_token())
+    }
+
+    /// Interpreta los tokens que siguen a `[` dentro de una definición hasta encontrar `]`.
+    fn interpret_until_bracket(&mut self) -> Result<(), ForthError> {
+        let compiling = self.compiling.take();
+        let result = loop {
+            match self.next_token() {
+                None => {
+                    break Err(ForthError::MissingTerminator {
+                        terminator: "]",
+                        opener: "[",
+                    });
+                }
+                Some(token) if token == "]" => break Ok(()),
+                Some(token) => {
+                    if let Err(e) = self.process_token(&token) {
+                        break Err(e);
+                    }
+                }
+            }
+        };
+        self.compiling = compiling;
+        result
+    }
+
     /// Compila y procesa una estructura condicional comenzando con IF.
-    fn compile_if(&mut self) -> Result<(), String> {
-        let mut true_branch = Vec::new();
+    fn compile_if(&mut self) -> Result<(), ForthError> {
+        let if_token = self.current_token();
+        let mut true_branch = Body::default();
         let false_branch = loop {
-            let token = self.next_token().ok_or("Missing THEN for IF".to_string())?;
+            let token = self
+                .next_compile_token()
+                .ok_or(ForthError::MissingTerminator {
+                    terminator: "THEN",
+                    opener: "IF",
+                })?;
             let token_upper = token.to_uppercase();
             if token_upper == "ELSE" {
-                break Some(self.compile_until("THEN")?);
+                break Some(self.compile_until("THEN", "IF")?);
             } else if token_upper == "THEN" {
                 break None;
-            } else if token_upper == "IF" {
-                let nested_if = self.compile_if_internal()?;
-                true_branch.push(Rc::new(nested_if));
-            } else if token_upper == ".\"" {
-                let literal = self.next_token().ok_or("Missing closing quote for .\"")?;
-                true_branch.push(Rc::new(Word::StringLiteral(
-                    literal.trim_start().to_owned(),
-                )));
             } else {
-                let word = self.resolve_token(&token)?;
-                true_branch.push(word);
+                true_branch.extend(self.compile_token(&token)?);
             }
         };
 
         if let Some((_, ref mut words)) = self.compiling {
-            words.push(Rc::new(Word::If {
-                true_branch,
-                false_branch,
-            }));
+            words.push(
+                Rc::new(Word::If {
+                    true_branch,
+                    false_branch,
+                }),
+                if_token,
+            );
         }
         Ok(())
     }
 
     /// Función auxiliar recursiva para compilar un IF anidado.
-    fn compile_if_internal(&mut self) -> Result<Word, String> {
-        let mut true_branch = Vec::new();
+    fn compile_if_internal(&mut self) -> Result<Word, ForthError> {
+        let mut true_branch = Body::default();
         let false_branch = loop {
             let token = self
-                .next_token()
-                .ok_or("Missing THEN for nested IF".to_string())?;
+                .next_compile_token()
+                .ok_or(ForthError::MissingTerminator {
+                    terminator: "THEN",
+                    opener: "nested IF",
+                })?;
             let token_upper = token.to_uppercase();
 
             if token_upper == "ELSE" {
-                break Some(self.compile_until("THEN")?);
+                break Some(self.compile_until("THEN", "IF")?);
             } else if token_upper == "THEN" {
                 break None;
-            } else if token_upper == "IF" {
-                let nested = self.compile_if_internal()?;
-                true_branch.push(Rc::new(nested));
-            } else if token_upper == ".\"" {
-                let literal = self.next_token().ok_or("Missing closing quote for .\"")?;
-                true_branch.push(Rc::new(Word::StringLiteral(
-                    literal.trim_start().to_owned(),
-                )));
             } else {
-                let word = self.resolve_token(&token)?;
-                true_branch.push(word);
+                true_branch.extend(self.compile_token(&token)?);
             }
         };
 
@@ -468,118 +1569,543 @@ impl Interpreter {
         })
     }
 
-    /// Compila tokens hasta encontrar el token objetivo respetando IF anidados.
-    fn compile_until(&mut self, target: &str) -> Result<Vec<Rc<Word>>, String> {
-        let mut words = Vec::new();
+    /// Compila tokens hasta encontrar el token objetivo respetando estructuras anidadas.
+    fn compile_until(
+        &mut self,
+        target: &'static str,
+        opener: &'static str,
+    ) -> Result<Body, ForthError> {
+        let mut words = Body::default();
 
         loop {
             let token = self
-                .next_token()
-                .ok_or(format!("Missing {} for IF", target))?;
+                .next_compile_token()
+                .ok_or(ForthError::MissingTerminator {
+                    terminator: target,
+                    opener,
+                })?;
             if token.to_uppercase() == target.to_uppercase() {
                 break;
             }
+            words.extend(self.compile_token(&token)?);
+        }
+        Ok(words)
+    }
+
+    /// Compila un bucle contado comenzando con DO hasta encontrar su LOOP o +LOOP.
+    fn compile_do(&mut self) -> Result<Word, ForthError> {
+        self.open_do_loops += 1;
+        let result = self.compile_do_body();
+        self.open_do_loops -= 1;
+        result
+    }
+
+    /// Compila el cuerpo de un bucle contado hasta su LOOP o +LOOP.
+    fn compile_do_body(&mut self) -> Result<Word, ForthError> {
+        let mut body = Body::default();
+        loop {
+            let token = self
+                .next_compile_token()
+                .ok_or(ForthError::MissingTerminator {
+                    terminator: "LOOP",
+                    opener: "DO",
+                })?;
             match token.to_uppercase().as_str() {
-                "IF" => {
-                    let nested = self.compile_if_internal()?;
-                    words.push(Rc::new(nested));
+                "LOOP" => {
+                    return Ok(Word::DoLoop {
+                        body,
+                        plus_loop: false,
+                    });
                 }
-                ".\"" => {
-                    let literal = self.next_token().ok_or("Missing closing quote for .\"")?;
-                    let literal = literal.trim_start().to_owned();
-                    words.push(Rc::new(Word::StringLiteral(literal)));
+                "+LOOP" => {
+                    return Ok(Word::DoLoop {
+                        body,
+                        plus_loop: true,
+                    });
                 }
+                _ => body.extend(self.compile_token(&token)?),
+            }
+        }
+    }
 
-                _ => {
-                    let word = self.resolve_token(&token)?;
-                    words.push(word);
+    /// Compila un bucle indefinido comenzando con BEGIN hasta su UNTIL, AGAIN o WHILE ... REPEAT.
+    fn compile_begin(&mut self) -> Result<Word, ForthError> {
+        let mut body = Body::default();
+        loop {
+            let token = self
+                .next_compile_token()
+                .ok_or(ForthError::MissingTerminator {
+                    terminator: "UNTIL",
+                    opener: "BEGIN",
+                })?;
+            match token.to_uppercase().as_str() {
+                "UNTIL" => return Ok(Word::BeginUntil { body }),
+                "AGAIN" => return Ok(Word::BeginAgain { body }),
+                "WHILE" => {
+                    let repeat_body = self.compile_until("REPEAT", "WHILE")?;
+                    return Ok(Word::BeginWhile {
+                        condition: body,
+                        body: repeat_body,
+                    });
                 }
+                _ => body.extend(self.compile_token(&token)?),
             }
         }
-        Ok(words)
+    }
+
+    /// Compila un token dentro de una estructura de control, resolviendo las estructuras anidadas.
+    ///
+    /// Las words inmediatas se ejecutan en el momento y solo producen las words que agreguen
+    /// con `POSTPONE`. Todas las words compiladas quedan asociadas al token actual.
+    fn compile_token(&mut self, token: &str) -> Result<Body, ForthError> {
+        let source_token = self.current_token();
+        let token_upper = token.to_uppercase();
+        let mut compiled = Body::default();
+        let word = match token_upper.as_str() {
+            "IF" => Rc::new(self.compile_if_internal()?),
+            "DO" => Rc::new(self.compile_do()?),
+            "BEGIN" => Rc::new(self.compile_begin()?),
+            "TO" => Rc::new(Word::ToValue(self.read_value_address()?)),
+            ".\"" => {
+                let literal = self.next_token().ok_or(ForthError::UnterminatedString)?;
+                Rc::new(Word::StringLiteral(literal.trim_start().to_owned()))
+            }
+            "POSTPONE" => {
+                let name = self.next_token().ok_or(ForthError::InvalidWord)?;
+                self.compile_postpone(&name)?
+            }
+            "LITERAL" => Rc::new(Word::Number(self.stack.pop()?)),
+            "RECURSE" if self.compiling.is_some() => {
+                Rc::new(Word::Recurse(self.self_reference.clone()))
+            }
+            "RECURSIVE" if self.compiling.is_some() => {
+                self.recursive = true;
+                return Ok(compiled);
+            }
+            "[']" => Rc::new(Word::Number(self.read_execution_token()?)),
+            "[" => {
+                self.interpret_until_bracket()?;
+                return Ok(compiled);
+            }
+            "]" => return Err(ForthError::UnexpectedToken),
+            "LEAVE" | "UNLOOP" if self.open_do_loops == 0 => {
+                return Err(ForthError::UnexpectedToken);
+            }
+            _ if STRUCTURE_TERMINATORS.contains(&token_upper.as_str()) => {
+                return Err(ForthError::UnexpectedToken);
+            }
+            _ if self.recursive
+                && matches!(&self.compiling, Some((name, _)) if *name == token_upper) =>
+            {
+                Rc::new(Word::Recurse(self.self_reference.clone()))
+            }
+            _ => {
+                let word = self.resolve_token(token)?;
+                if self.is_immediate(&token_upper) {
+                    for word in self.run_immediate(&word)? {
+                        compiled.push(word, source_token.clone());
+                    }
+                    return Ok(compiled);
+                }
+                word
+            }
+        };
+        compiled.push(word, source_token);
+        Ok(compiled)
+    }
+
+    /// Indica si la entrada del diccionario con el nombre indicado está marcada con `IMMEDIATE`.
+    fn is_immediate(&self, name: &str) -> bool {
+        self.dict.get(name).is_some_and(|entry| entry.immediate)
+    }
+
+    /// Ejecuta una word inmediata dentro de una definición y devuelve las words que agregó
+    /// a la definición con `POSTPONE`.
+    ///
+    /// Cada ejecución junta sus propias words, así las que compila una estructura abierta por
+    /// la word inmediata no se mezclan con las que la word agregó antes de abrirla.
+    fn run_immediate(&mut self, word: &Rc<Word>) -> Result<Vec<Rc<Word>>, ForthError> {
+        let outer = std::mem::take(&mut self.postponed);
+        let result = self.execute_word(word);
+        let words = std::mem::replace(&mut self.postponed, outer);
+        result.map(|()| words)
+    }
+
+    /// Compila `POSTPONE` resolviendo en este momento la word indicada.
+    ///
+    /// Las words del compilador (`IF`, `DO`, `THEN`, ...) posterga su efecto sobre la
+    /// definición; una word inmediata se compila tal cual, para que se ejecute junto con la
+    /// definición; cualquier otra se agregará a la definición en curso al ejecutarse esta.
+    fn compile_postpone(&mut self, name: &str) -> Result<Rc<Word>, ForthError> {
+        let name_upper = name.to_uppercase();
+        if COMPILER_WORDS.contains(&name_upper.as_str())
+            || STRUCTURE_TERMINATORS.contains(&name_upper.as_str())
+        {
+            return Ok(Rc::new(Word::PostponedSyntax(name_upper)));
+        }
+        let entry = self
+            .dict
+            .get(&name_upper)
+            .ok_or_else(|| ForthError::UnknownWord(name.to_string()))?;
+        if entry.immediate {
+            Ok(Rc::clone(&entry.word))
+        } else {
+            Ok(Rc::new(Word::Postponed(Rc::clone(&entry.word))))
+        }
+    }
+
+    /// Aplica a la definición en curso una word del compilador postergada con `POSTPONE`.
+    ///
+    /// Un cierre de estructura queda pendiente para la estructura que se está compilando;
+    /// el resto se compila como si la word apareciera en la definición.
+    fn run_postponed_syntax(&mut self, name: &str) -> Result<(), ForthError> {
+        if self.compiling.is_none() {
+            return Err(ForthError::CompileOnlyWord);
+        }
+        if STRUCTURE_TERMINATORS.contains(&name) {
+            self.postponed_terminator = Some(name.to_string());
+            return Ok(());
+        }
+        let words = self.compile_token(name)?;
+        self.postponed.extend(words.into_words());
+        Ok(())
     }
 
     /// Resuelve un token buscando en el diccionario o interpretándolo como número.
-    fn resolve_token(&self, token: &str) -> Result<Rc<Word>, String> {
+    fn resolve_token(&self, token: &str) -> Result<Rc<Word>, ForthError> {
         let token_upper = token.to_uppercase();
-        if let Some(word) = self.dict.get(&token_upper) {
-            Ok(Rc::clone(word))
-        } else if let Ok(number) = token.parse::<i16>() {
+        if let Some(entry) = self.dict.get(&token_upper) {
+            Ok(Rc::clone(&entry.word))
+        } else if let Some(number) = self.cell.parse(token) {
             Ok(Rc::new(Word::Number(number)))
         } else {
-            Err("?".to_string())
+            Err(ForthError::UnknownWord(token.to_string()))
         }
     }
 
     /// Ejecuta un word en el contexto actual.
-    fn run_word(&mut self, word: &Rc<Word>) -> Result<(), String> {
+    fn run_word(&mut self, word: &Rc<Word>) -> Result<(), ForthError> {
         match &**word {
             Word::Number(n) => self.run_number(*n),
-            Word::Words(words) => self.run_words(words),
+            Word::Words(words) => self.run_definition(word, words),
             Word::Builtin(op) => self.run_builtin(op),
-            Word::StringLiteral(s) => {
-                print!("{}", s);
-                Ok(())
-            }
+            Word::Native(native) => native.call(&mut self.stack, &mut self.data_space),
+            Word::StringLiteral(s) => self.write_output(format_args!("{}", s)),
             Word::If {
                 true_branch,
                 false_branch,
-            } => {
-                let cond = self.stack.pop()?;
-                if cond != 0 {
-                    self.run_words(true_branch)?;
-                } else if let Some(false_branch) = false_branch {
-                    self.run_words(false_branch)?;
+            } => self.run_if(true_branch, false_branch.as_ref()),
+            Word::DataField(addr) => self.stack.push(self.data_space.cell_from_address(*addr)),
+            Word::DataFieldDoes { addr, body } => {
+                self.stack.push(self.data_space.cell_from_address(*addr))?;
+                self.run_word(body)
+            }
+            Word::Does(body) => self.run_does(body),
+            Word::Recurse(self_reference) => {
+                let word = self_reference.get().ok_or(ForthError::InvalidWord)?;
+                self.run_word(&word)
+            }
+            Word::Postponed(postponed) => {
+                if self.compiling.is_none() {
+                    return Err(ForthError::CompileOnlyWord);
                 }
+                self.postponed.push(Rc::clone(postponed));
                 Ok(())
             }
+            Word::PostponedSyntax(name) => self.run_postponed_syntax(name),
+            Word::Value(addr) => {
+                let value = self.data_space.fetch(*addr)?;
+                self.stack.push(value)
+            }
+            Word::ToValue(addr) => {
+                let value = self.stack.pop()?;
+                self.data_space.store(*addr, value)
+            }
+            Word::DoLoop { body, plus_loop } => self.run_do_loop(body, *plus_loop),
+            Word::BeginUntil { body } => self.run_begin_until(body),
+            Word::BeginWhile { condition, body } => self.run_begin_while(condition, body),
+            Word::BeginAgain { body } => self.run_begin_again(body),
+        }
+    }
+
+    /// Ejecuta la rama correspondiente de un condicional según el tope de la pila.
+    fn run_if(
+        &mut self,
+        true_branch: &Body,
+        false_branch: Option<&Body>,
+    ) -> Result<(), ForthError> {
+        let cond = self.stack.pop()?;
+        if cond != 0 {
+            self.run_words(true_branch)?;
+        } else if let Some(false_branch) = false_branch {
+            self.run_words(false_branch)?;
+        }
+        Ok(())
+    }
+
+    fn run_begin_until(&mut self, body: &Body) -> Result<(), ForthError> {
+        loop {
+            self.run_words(body)?;
+            if self.pending.is_some() || self.stack.pop()? != 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    fn run_begin_while(&mut self, condition: &Body, body: &Body) -> Result<(), ForthError> {
+        loop {
+            self.run_words(condition)?;
+            if self.pending.is_some() || self.stack.pop()? == 0 {
+                return Ok(());
+            }
+            self.run_words(body)?;
+            if self.pending.is_some() {
+                return Ok(());
+            }
         }
     }
 
-    fn run_number(&mut self, n: i16) -> Result<(), String> {
+    fn run_begin_again(&mut self, body: &Body) -> Result<(), ForthError> {
+        loop {
+            self.run_words(body)?;
+            if self.pending.is_some() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Ejecuta el cuerpo de una word definida por el usuario.
+    ///
+    /// Si la ejecución falla, se conserva la cadena de definiciones en curso para informarla.
+    fn run_definition(&mut self, word: &Rc<Word>, words: &Body) -> Result<(), ForthError> {
+        self.call_stack.push(Rc::clone(word));
+        let result = self.run_words(words);
+        if result.is_err() {
+            self.backtrace
+                .get_or_insert_with(|| self.call_stack.clone());
+        }
+        self.call_stack.pop();
+        result?;
+        if self.pending == Some(ControlSignal::Exit) {
+            self.pending = None;
+        }
+        Ok(())
+    }
+
+    /// Ejecuta un bucle contado tomando el límite y el índice inicial de la pila.
+    ///
+    /// El marco del bucle se descarta al finalizar aunque la ejecución falle.
+    fn run_do_loop(&mut self, body: &Body, plus_loop: bool) -> Result<(), ForthError> {
+        let start = self.stack.pop()?;
+        let limit = self.stack.pop()?;
+        let frame = self.loop_stack.len();
+        self.loop_stack.push((start, limit));
+        let result = self.run_loop_iterations(body, plus_loop, frame);
+        self.loop_stack.truncate(frame);
+        result
+    }
+
+    /// Repite el cuerpo del bucle hasta que el índice cruce la frontera entre `límite - 1` y `límite`.
+    fn run_loop_iterations(
+        &mut self,
+        body: &Body,
+        plus_loop: bool,
+        frame: usize,
+    ) -> Result<(), ForthError> {
+        loop {
+            self.run_words(body)?;
+            match self.pending {
+                Some(ControlSignal::Leave) => {
+                    self.pending = None;
+                    return Ok(());
+                }
+                Some(ControlSignal::Exit) => return Ok(()),
+                None => {}
+            }
+            if self.loop_stack.len() <= frame {
+                return Ok(());
+            }
+            let step = if plus_loop { self.stack.pop()? } else { 1 };
+            let (index, limit) = self.loop_stack[frame];
+            let old_diff = self.cell.wrap(index.wrapping_sub(limit));
+            let new_diff = self.cell.wrap(old_diff.wrapping_add(step));
+            if (old_diff ^ new_diff) & (old_diff ^ step) < 0 {
+                return Ok(());
+            }
+            self.loop_stack[frame].0 = self.cell.wrap(index.wrapping_add(step));
+        }
+    }
+
+    fn run_number(&mut self, n: Cell) -> Result<(), ForthError> {
         self.stack.push(n)
     }
 
-    fn run_words(&mut self, words: &[Rc<Word>]) -> Result<(), String> {
-        for w in words {
-            self.run_word(w)?;
+    /// Ejecuta una secuencia de words: el cuerpo de una definición o de una estructura
+    /// de control.
+    ///
+    /// Cada secuencia en ejecución ocupa un nivel de anidamiento; se limita la cantidad de
+    /// niveles para que una recursión sin fin produzca un error de "return-stack-overflow"
+    /// en lugar de agotar la pila del hilo.
+    fn run_words(&mut self, words: &Body) -> Result<(), ForthError> {
+        if self.nesting >= self.max_call_depth {
+            self.backtrace
+                .get_or_insert_with(|| self.call_stack.clone());
+            return Err(ForthError::ReturnStackOverflow);
+        }
+        self.nesting += 1;
+        let result = self.run_each(words);
+        self.nesting -= 1;
+        result
+    }
+
+    /// Ejecuta cada word de la secuencia. Si una falla, se conserva el token que la compiló
+    /// (salvo que una word más interna ya haya registrado el suyo).
+    fn run_each(&mut self, words: &Body) -> Result<(), ForthError> {
+        for (index, word) in words.words().iter().enumerate() {
+            if let Err(e) = self.run_word(word) {
+                self.error_token
+                    .get_or_insert_with(|| words.token(index).clone());
+                return Err(e);
+            }
+            if self.pending.is_some() {
+                break;
+            }
         }
         Ok(())
     }
 
-    fn run_builtin(&mut self, op: &str) -> Result<(), String> {
+    fn run_builtin(&mut self, op: &str) -> Result<(), ForthError> {
         match op {
             "+" => self.apply_binary_op(|a, b| a + b),
             "-" => self.apply_binary_op(|a, b| a - b),
             "*" => self.apply_binary_op(|a, b| a * b),
             "/" => self.handle_division(),
+            "MOD" => self.handle_mod(),
+            "/MOD" => self.handle_slash_mod(),
+            "*/" => self.handle_star_slash(false),
+            "*/MOD" => self.handle_star_slash(true),
+            "FM/MOD" => self.handle_double_division(true),
+            "SM/REM" => self.handle_double_division(false),
+            "NEGATE" => self.apply_unary_op(|a| -a),
+            "ABS" => self.apply_unary_op(|a| a.abs()),
+            "MIN" => self.apply_binary_op(|a, b| a.min(b)),
+            "MAX" => self.apply_binary_op(|a, b| a.max(b)),
+            "1+" => self.apply_unary_op(|a| a + 1),
+            "1-" => self.apply_unary_op(|a| a - 1),
+            "2*" => {
+                let a = self.stack.pop()?;
+                self.stack.push(self.cell.wrap(a << 1))
+            }
+            "2/" => {
+                let a = self.stack.pop()?;
+                self.stack.push(a >> 1)
+            }
             "DUP" => self.handle_dup(),
             "SWAP" => self.handle_swap(),
             "DROP" => self.handle_drop(),
             "ROT" => self.handle_rot(),
             "OVER" => self.handle_over(),
+            "NIP" => self.handle_nip(),
+            "TUCK" => self.handle_tuck(),
+            "PICK" => self.handle_pick(),
+            "ROLL" => self.handle_roll(),
+            "-ROT" => self.handle_minus_rot(),
+            "?DUP" => self.handle_question_dup(),
+            "2DUP" => self.handle_two_dup(),
+            "2DROP" => self.handle_two_drop(),
+            "2SWAP" => self.handle_two_swap(),
+            "2OVER" => self.handle_two_over(),
+            "DEPTH" => self.stack.push(self.stack.depth() as Cell),
+            ">R" => self.handle_to_r(),
+            "R>" => self.handle_r_from(),
+            "R@" => self.handle_r_fetch(),
+            "2>R" => self.handle_two_to_r(),
+            "2R>" => self.handle_two_r_from(),
+            "2R@" => self.handle_two_r_fetch(),
+            "VARIABLE" => self.handle_variable(),
+            "CONSTANT" => self.handle_constant(),
+            "VALUE" => self.handle_value(),
+            "TO" => self.handle_to(),
+            "@" => self.handle_fetch(),
+            "!" => self.handle_store(),
+            "+!" => self.handle_plus_store(),
+            "C@" => self.handle_c_fetch(),
+            "C!" => self.handle_c_store(),
+            "IMMEDIATE" => self.handle_immediate(),
+            "'" => self.handle_tick(),
+            "EXECUTE" => self.handle_execute(),
+            "CATCH" => self.handle_catch(),
+            "THROW" => self.handle_throw(),
+            "DO" | "LOOP" | "+LOOP" | "BEGIN" | "UNTIL" | "WHILE" | "REPEAT" | "AGAIN"
+            | "DOES>" | "POSTPONE" | "[" | "]" | "LITERAL" | "[']" | "RECURSE" | "RECURSIVE" => {
+                Err(ForthError::CompileOnlyWord)
+            }
+            "CREATE" => self.handle_create(),
+            "HERE" => {
+                let here = self.data_space.cell_from_address(self.data_space.here());
+                self.stack.push(here)
+            }
+            "ALLOT" => self.handle_allot(),
+            "," => self.handle_comma(),
+            "C," => self.handle_c_comma(),
+            "ALIGN" => self.data_space.align(),
+            "CELLS" => {
+                let n = self.stack.pop()?;
+                let size = self.data_space.cell_size() as Cell;
+                self.stack.push(self.cell.wrap(n.wrapping_mul(size)))
+            }
+            "CELL+" => {
+                let addr = self.stack.pop()?;
+                let size = self.data_space.cell_size() as Cell;
+                self.stack.push(self.cell.wrap(addr.wrapping_add(size)))
+            }
             "NOT" => self.handle_not(),
             "EMIT" => self.handle_emit(),
-            "AND" => self.handle_and(),
-            "OR" => self.handle_or(),
+            "KEY" => self.handle_key(),
+            "KEY?" => self.handle_key_question(),
+            "ACCEPT" => self.handle_accept(),
+            "AND" => self.apply_bitwise_op(|a, b| a & b),
+            "OR" => self.apply_bitwise_op(|a, b| a | b),
+            "XOR" => self.apply_bitwise_op(|a, b| a ^ b),
+            "INVERT" => self.handle_invert(),
+            "LSHIFT" => self.handle_shift(true),
+            "RSHIFT" => self.handle_shift(false),
             "=" => self.handle_equals(),
             "<" => self.handle_less_than(),
             ">" => self.handle_greater_than(),
+            "<>" => self.apply_binary_op(|a, b| if a != b { -1 } else { 0 }),
+            "<=" => self.apply_binary_op(|a, b| if a <= b { -1 } else { 0 }),
+            ">=" => self.apply_binary_op(|a, b| if a >= b { -1 } else { 0 }),
+            "0=" => self.apply_unary_op(|a| if a == 0 { -1 } else { 0 }),
+            "0<" => self.apply_unary_op(|a| if a < 0 { -1 } else { 0 }),
+            "0>" => self.apply_unary_op(|a| if a > 0 { -1 } else { 0 }),
+            "U<" => self.handle_unsigned_comparison(true),
+            "U>" => self.handle_unsigned_comparison(false),
+            "WITHIN" => self.handle_within(),
             "IF" => self.handle_if(),
             "ELSE" => self.handle_else(),
             "THEN" => self.handle_then(),
+            "I" => self.handle_loop_index(0),
+            "J" => self.handle_loop_index(1),
+            "LEAVE" => {
+                self.pending = Some(ControlSignal::Leave);
+                Ok(())
+            }
+            "UNLOOP" => self.handle_unloop(),
+            "EXIT" => {
+                self.pending = Some(ControlSignal::Exit);
+                Ok(())
+            }
             "CR" => {
-                println!();
                 self.last_was_dot_quote = false;
-                Ok(())
+                self.write_output(format_args!("\n"))
             }
             "." => {
-                let val = self.stack.pop().map_err(|e| e.to_string())?;
-                print!("{} ", val);
-                Ok(())
+                let val = self.stack.pop()?;
+                self.write_output(format_args!("{} ", val))
             }
             ".\"" => self.handle_dot_quote(),
-            _ => Err("invalid-word".to_string()),
+            _ => Err(ForthError::InvalidWord),
         }
     }
 }
@@ -587,76 +2113,544 @@ impl Interpreter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::output::OutputBuffer;
+    use std::cell::RefCell;
 
     #[test]
     fn test_arithmetic_operations() {
         let mut interpreter = Interpreter::new(1024);
-        interpreter.parse_line("1 2 +").unwrap();
+        interpreter.eval("1 2 +").unwrap();
         assert_eq!(interpreter.stack_to_vec(), vec![3]);
 
-        interpreter.parse_line("10 5 -").unwrap();
+        interpreter.eval("10 5 -").unwrap();
         assert_eq!(interpreter.stack_to_vec(), vec![3, 5]);
 
-        interpreter.parse_line("3 4 *").unwrap();
+        interpreter.eval("3 4 *").unwrap();
         assert_eq!(interpreter.stack_to_vec(), vec![3, 5, 12]);
 
-        interpreter.parse_line("20 4 /").unwrap();
+        interpreter.eval("20 4 /").unwrap();
         assert_eq!(interpreter.stack_to_vec(), vec![3, 5, 12, 5]);
     }
 
     #[test]
     fn test_stack_operations() {
         let mut interpreter = Interpreter::new(1024);
-        interpreter.parse_line("1 2 3").unwrap();
+        interpreter.eval("1 2 3").unwrap();
         assert_eq!(interpreter.stack_to_vec(), vec![1, 2, 3]);
 
-        interpreter.parse_line("DUP").unwrap();
+        interpreter.eval("DUP").unwrap();
         assert_eq!(interpreter.stack_to_vec(), vec![1, 2, 3, 3]);
 
-        interpreter.parse_line("SWAP").unwrap();
+        interpreter.eval("SWAP").unwrap();
         assert_eq!(interpreter.stack_to_vec(), vec![1, 2, 3, 3]);
 
-        interpreter.parse_line("DROP").unwrap();
+        interpreter.eval("DROP").unwrap();
         assert_eq!(interpreter.stack_to_vec(), vec![1, 2, 3]);
     }
 
     #[test]
     fn test_define_and_execute_word() {
         let mut interpreter = Interpreter::new(1024);
-        interpreter.parse_line(": SQUARE DUP * ;").unwrap();
-        interpreter.parse_line("4 SQUARE").unwrap();
+        interpreter.eval(": SQUARE DUP * ;").unwrap();
+        interpreter.eval("4 SQUARE").unwrap();
         assert_eq!(interpreter.stack_to_vec(), vec![16]);
     }
 
     #[test]
     fn test_conditional_execution() {
         let mut interpreter = Interpreter::new(1024);
-        interpreter.parse_line("1 IF 42 ELSE 99 THEN").unwrap();
+        interpreter.eval("1 IF 42 ELSE 99 THEN").unwrap();
         assert_eq!(interpreter.stack_to_vec(), vec![42]);
 
-        interpreter.parse_line("0 IF 42 ELSE 99 THEN").unwrap();
+        interpreter.eval("0 IF 42 ELSE 99 THEN").unwrap();
         assert_eq!(interpreter.stack_to_vec(), vec![42, 99]);
     }
 
     #[test]
     fn test_error_handling() {
         let mut interpreter = Interpreter::new(1024);
-        let result = interpreter.parse_line("1 0 /");
+        let result = interpreter.eval("1 0 /");
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "division-by-zero".to_string());
+        assert_eq!(result.unwrap_err(), ForthError::DivisionByZero);
     }
 
     #[test]
     fn test_output_operations() {
         let mut interpreter = Interpreter::new(1024);
-        interpreter.parse_line("65 EMIT").unwrap();
+        let output = OutputBuffer::new();
+        interpreter.set_output(output.clone());
+        interpreter.eval("65 EMIT").unwrap();
         assert_eq!(interpreter.stack_to_vec(), vec![]);
+        assert_eq!(output.take(), "A ");
+
+        interpreter
+            .eval(": greet .\" hola\" ; 1 2 . . CR greet .\" a\" .\" todos\"")
+            .unwrap();
+        assert_eq!(output.take(), "2 1 \nholaa todos");
+    }
+
+    #[test]
+    fn test_do_loop() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter.eval(": COUNT 5 0 DO I LOOP ;").unwrap();
+        interpreter.eval("COUNT").unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_do_plus_loop_top_level() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter.eval("0 10 DO I -3 +LOOP").unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![10, 7, 4, 1]);
+    }
+
+    #[test]
+    fn test_begin_until() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter.eval("3 BEGIN DUP 1 - DUP 0 = UNTIL").unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_return_stack_operations() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter.eval("1 2 >R 3 R@ R> +").unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![1, 3, 4]);
+
+        interpreter.eval("2>R 2R@ 2R>").unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![1, 3, 4, 3, 4]);
+    }
+
+    #[test]
+    fn test_limited_return_stack() {
+        let mut interpreter = Interpreter::with_return_stack_size(1024, 1);
+        let result = interpreter.eval("1 2 >R >R");
+        assert_eq!(result, Err(ForthError::ReturnStackOverflow));
+    }
+
+    #[test]
+    fn test_variable_and_constant() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter
+            .eval("VARIABLE X 10 CONSTANT TEN TEN X ! 5 X +! X @")
+            .unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![15]);
+    }
+
+    #[test]
+    fn test_value_to() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter.eval("1 VALUE V : SET TO V ;").unwrap();
+        interpreter.eval("V 7 SET V 9 TO V V").unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![1, 7, 9]);
+    }
+
+    #[test]
+    fn test_address_out_of_range() {
+        let mut interpreter = Interpreter::new(1024);
+        let result = interpreter.eval("-1 @");
+        assert_eq!(result, Err(ForthError::AddressOutOfRange));
+    }
+
+    #[test]
+    fn test_create_allot() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter
+            .eval("CREATE TABLE 1 , 2 , 3 , TABLE CELL+ @ HERE TABLE - 3 CELLS =")
+            .unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![2, -1]);
+    }
+
+    #[test]
+    fn test_create_does() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter
+            .eval(": CONST CREATE , DOES> @ ; 7 CONST SEVEN 9 CONST NINE")
+            .unwrap();
+        interpreter.eval("SEVEN NINE SEVEN").unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![7, 9, 7]);
+    }
+
+    #[test]
+    fn test_immediate_postpone() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter
+            .eval(": UNLESS POSTPONE NOT POSTPONE IF ; IMMEDIATE")
+            .unwrap();
+        interpreter
+            .eval(": F UNLESS 10 ELSE 20 THEN ; 0 F -1 F")
+            .unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![10, 20]);
+    }
+
+    #[test]
+    fn test_postpone_binds_at_compile_time() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter
+            .eval(": A 1 ; : B POSTPONE A ; IMMEDIATE : A 2 ; : C B ; C")
+            .unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![1]);
+    }
+
+    #[test]
+    fn test_postpone_through_execute() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter
+            .eval(": B POSTPONE DUP ; IMMEDIATE : X ['] B EXECUTE ; IMMEDIATE")
+            .unwrap();
+        interpreter.eval(": D X + ; 4 D").unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![8]);
+        assert_eq!(
+            interpreter.eval("' B EXECUTE"),
+            Err(ForthError::CompileOnlyWord)
+        );
+    }
+
+    #[test]
+    fn test_immediate_flag_on_entry() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter
+            .eval(": X 5 ; IMMEDIATE : Y X ; : X 6 ; : Z X ; Z")
+            .unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![5, 6]);
+    }
+
+    #[test]
+    fn test_brackets_literal() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter.eval(": F [ 3 4 * ] LITERAL ; F").unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![12]);
+    }
+
+    #[test]
+    fn test_recurse() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter
+            .eval(": FACT DUP 1 > IF DUP 1 - RECURSE * THEN ; 5 FACT")
+            .unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![120]);
+    }
+
+    #[test]
+    fn test_recursive_marker() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter
+            .eval(": SUM RECURSIVE DUP IF DUP 1 - SUM + THEN ; 4 SUM")
+            .unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![10]);
+    }
+
+    #[test]
+    fn test_call_depth_limit() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter.set_max_call_depth(50);
+        let result = interpreter.eval(": F RECURSE ; F");
+        assert_eq!(result, Err(ForthError::ReturnStackOverflow));
+    }
+
+    #[test]
+    fn test_arithmetic_words() {
+        let mut interpreter = Interpreter::new(32);
+        interpreter
+            .eval("-7 2 MOD 7 -2 /MOD 20000 3 4 */ -7 -1 2 FM/MOD 7 0 -2 SM/REM")
+            .unwrap();
+        assert_eq!(
+            interpreter.stack_to_vec(),
+            vec![-1, 1, -3, 15000, 1, -4, 1, -3]
+        );
+        assert_eq!(interpreter.eval("1 0 MOD"), Err(ForthError::DivisionByZero));
+
+        interpreter.abort();
+        interpreter
+            .eval("-32768 ABS -32768 1- 16384 2* 1 2/")
+            .unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![-32768, 32767, -32768, 0]);
+
+        interpreter.set_checked_arithmetic(true);
+        assert_eq!(
+            interpreter.eval("-32768 NEGATE"),
+            Err(ForthError::ArithmeticOverflow)
+        );
+        assert_eq!(
+            interpreter.eval("0 1 1 FM/MOD"),
+            Err(ForthError::ArithmeticOverflow)
+        );
+
+        let mut interpreter = Interpreter::with_cell_width(16, 16, CellWidth::Bits64);
+        interpreter
+            .eval("0 -9223372036854775808 -1 FM/MOD 0 -9223372036854775808 -1 SM/REM")
+            .unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![0, 0, 0, 0]);
+        interpreter.set_checked_arithmetic(true);
+        assert_eq!(
+            interpreter.eval("0 -9223372036854775808 -1 SM/REM"),
+            Err(ForthError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn test_bitwise_operations() {
+        let mut interpreter = Interpreter::new(32);
+        interpreter
+            .eval("12 10 AND 12 10 OR 12 10 XOR 0 INVERT 1 4 LSHIFT -1 12 RSHIFT 1 16 LSHIFT")
+            .unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![8, 14, 6, -1, 16, 15, 0]);
+
+        interpreter.abort();
+        interpreter.set_logical_flags(true);
+        interpreter
+            .eval("12 10 AND 2 0 OR 12 10 XOR 5 INVERT 0 INVERT 1 4 LSHIFT")
+            .unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![-1, -1, 0, 0, -1, 16]);
+    }
+
+    #[test]
+    fn test_comparison_words() {
+        let mut interpreter = Interpreter::new(32);
+        interpreter
+            .eval("1 2 <> 2 2 <= 1 2 >= 0 0= -5 0< 5 0> -1 1 U< -1 1 U> 2 1 3 WITHIN 3 1 3 WITHIN")
+            .unwrap();
+        assert_eq!(
+            interpreter.stack_to_vec(),
+            vec![-1, -1, 0, -1, -1, -1, 0, -1, -1, 0]
+        );
+
+        interpreter.abort();
+        interpreter
+            .eval("32767 32767 -32767 WITHIN -32768 32767 -32767 WITHIN 0 32767 -32767 WITHIN")
+            .unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![-1, -1, 0]);
+
+        let mut interpreter = Interpreter::with_cell_width(16, 16, CellWidth::Bits32);
+        interpreter.eval("-1 40000 U> 40000 -1 U<").unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![-1, -1]);
+    }
+
+    #[test]
+    fn test_arithmetic_overflow() {
+        let mut interpreter = Interpreter::new(16);
+        interpreter
+            .eval("32767 1 + -32768 1 - 200 200 * -32768 -1 /")
+            .unwrap();
+        assert_eq!(
+            interpreter.stack_to_vec(),
+            vec![-32768, 32767, -25536, -32768]
+        );
+
+        interpreter.set_checked_arithmetic(true);
+        interpreter.abort();
+        for code in ["32767 1 +", "-32768 1 -", "200 200 *", "-32768 -1 /"] {
+            assert_eq!(interpreter.eval(code), Err(ForthError::ArithmeticOverflow));
+        }
+        interpreter.eval("32766 1 + -32767 1 - 100 100 *").unwrap();
+
+        let mut interpreter = Interpreter::with_cell_width(16, 16, CellWidth::Bits64);
+        interpreter.set_checked_arithmetic(true);
+        assert_eq!(
+            interpreter.eval("9223372036854775807 1 +"),
+            Err(ForthError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn test_default_call_depth_limit() {
+        let mut interpreter = Interpreter::new(1024);
+        let result = interpreter.eval(": F RECURSIVE 1 IF F THEN ; F");
+        assert_eq!(result, Err(ForthError::ReturnStackOverflow));
+    }
+
+    #[test]
+    fn test_nested_structures_count_towards_call_depth() {
+        assert!(Interpreter::thread_stack_size(DEFAULT_MAX_CALL_DEPTH) <= 2 * 1024 * 1024);
+        let programs = [
+            ": F RECURSIVE 1 IF 1 0 DO BEGIN 1 WHILE 1 IF 1 IF 1 0 DO F LOOP THEN THEN REPEAT LOOP THEN ; F",
+            ": F RECURSIVE 1 IF 1 0 DO BEGIN 1 WHILE 1 IF 1 IF 1 0 DO 1 IF 1 0 DO BEGIN 1 IF 1 0 DO 1 IF F THEN LOOP THEN 1 UNTIL LOOP THEN LOOP THEN THEN REPEAT LOOP THEN ; F",
+            "VARIABLE V : F V @ EXECUTE ; ' F V ! F",
+            "VARIABLE V : D CREATE DOES> DROP V @ EXECUTE ; D X ' X V ! X",
+        ];
+        for program in programs {
+            let result = std::thread::Builder::new()
+                .stack_size(2 * 1024 * 1024)
+                .spawn(move || Interpreter::new(1024).eval(program))
+                .unwrap()
+                .join()
+                .unwrap();
+            assert_eq!(result, Err(ForthError::ReturnStackOverflow), "{}", program);
+        }
+    }
+
+    #[test]
+    fn test_catch_restores_stack() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter.eval("10 0 ' / CATCH").unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![10, 0, -10]);
+    }
+
+    #[test]
+    fn test_error_location() {
+        let mut interpreter = Interpreter::new(1024);
+        let error = interpreter
+            .parse_source("1 2 +\n  .\" hola\" foo")
+            .unwrap_err();
+        assert_eq!(error.error, ForthError::UnknownWord("foo".to_string()));
+        assert_eq!(
+            error.span,
+            Span {
+                line: 2,
+                column: 12
+            }
+        );
+        assert_eq!(error.token, "foo");
+        assert!(error.backtrace.is_empty());
+    }
+
+    #[test]
+    fn test_error_backtrace() {
+        let mut interpreter = Interpreter::new(1024);
+        let error = interpreter
+            .parse_source(": inner drop drop ;\n: outer inner ;\n1 outer")
+            .unwrap_err();
+        assert_eq!(error.error, ForthError::StackUnderflow);
+        assert_eq!(
+            error.span,
+            Span {
+                line: 1,
+                column: 14
+            }
+        );
+        assert_eq!(error.token, "drop");
+        assert_eq!(error.backtrace, vec!["INNER", "OUTER"]);
+
+        let error = interpreter
+            .parse_source("' inner catch drop 1 outer")
+            .unwrap_err();
+        assert_eq!(
+            error.span,
+            Span {
+                line: 1,
+                column: 14
+            }
+        );
+        assert_eq!(error.backtrace, vec!["INNER", "OUTER"]);
+
+        let error = interpreter
+            .parse_source(": nested 1 0 do 0 if else drop then loop ;\nnested")
+            .unwrap_err();
+        assert_eq!(
+            error.span,
+            Span {
+                line: 1,
+                column: 27
+            }
+        );
+        assert_eq!(error.token, "drop");
+        assert_eq!(error.backtrace, vec!["NESTED"]);
+    }
+
+    #[test]
+    fn test_is_incomplete() {
+        assert!(Interpreter::is_incomplete(": SQUARE DUP"));
+        assert!(Interpreter::is_incomplete("1 2 : A ; : B"));
+        assert!(!Interpreter::is_incomplete(": SQUARE DUP\n * ;"));
+        assert!(!Interpreter::is_incomplete("1 2 +"));
+    }
+
+    #[test]
+    fn test_abort_keeps_dictionary() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter.eval(": TWICE 2 * ; 7").unwrap();
+        assert!(interpreter.eval(": BROKEN 1 IF").is_err());
+        interpreter.abort();
+        assert_eq!(interpreter.stack_to_vec(), Vec::<Cell>::new());
+        interpreter.eval("3 TWICE").unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![6]);
+    }
+
+    #[test]
+    fn test_register_native() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter.register_native("square", |stack, _| {
+            let n = stack.pop()?;
+            stack.push(n * n)
+        });
+        interpreter.register_native("cell-sum", |stack, data_space| {
+            let addr = data_space.address(stack.pop()?);
+            let sum = data_space.fetch(addr)? + data_space.fetch(addr + data_space.cell_size())?;
+            stack.push(sum)
+        });
+        interpreter
+            .eval(": twice square square ; 3 twice create pair 4 , 5 , pair cell-sum")
+            .unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![81, 9]);
+
+        interpreter.register_native("fail", |_, _| Err(ForthError::Throw(7)));
+        interpreter.eval("' fail catch").unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![81, 9, 7]);
+    }
+
+    #[test]
+    fn test_key_and_accept() {
+        let mut interpreter = Interpreter::new(1024);
+        interpreter.set_input(&b"ab\r\nhola mundo\nfin"[..]);
+        interpreter.eval("KEY KEY? KEY").unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![97, -1, 98]);
+
+        interpreter
+            .eval("CREATE BUF 20 ALLOT BUF 20 ACCEPT BUF 20 ACCEPT BUF C@")
+            .unwrap();
+        assert_eq!(interpreter.stack_to_vec(), vec![97, -1, 98, 0, 10, 104]);
+
+        interpreter.eval("BUF 2 ACCEPT KEY KEY? KEY").unwrap();
+        assert_eq!(
+            interpreter.stack_to_vec(),
+            vec![97, -1, 98, 0, 10, 104, 2, 110, 0, -1]
+        );
+    }
+
+    /// Fuente de entrada que anota lo que ya se imprimió cada vez que se la lee.
+    struct PromptCheck {
+        output: OutputBuffer,
+        seen: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl io::Read for PromptCheck {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Ok(0)
+        }
+    }
+
+    impl BufRead for PromptCheck {
+        fn fill_buf(&mut self) -> io::Result<&[u8]> {
+            self.seen.borrow_mut().push(self.output.contents());
+            Ok(b"y")
+        }
+
+        fn consume(&mut self, _: usize) {}
+    }
+
+    #[test]
+    fn test_output_flushed_before_reading_input() {
+        let output = OutputBuffer::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut interpreter = Interpreter::new(1024);
+        interpreter.set_output(io::BufWriter::new(output.clone()));
+        interpreter.set_input(PromptCheck {
+            output: output.clone(),
+            seen: Rc::clone(&seen),
+        });
+        interpreter
+            .eval(r#"." tecla: " KEY DROP ." hay? " KEY? DROP ." linea: " HERE 1 ACCEPT DROP"#)
+            .unwrap();
+        assert_eq!(
+            *seen.borrow(),
+            vec!["tecla: ", "tecla:  hay? ", "tecla:  hay?  linea: "]
+        );
     }
 
     #[test]
     fn test_limited_stack() {
         let mut interpreter = Interpreter::new(2);
-        let result = interpreter.parse_line("1 2 3");
-        assert_eq!(result, Err("stack-overflow".to_string()));
+        let result = interpreter.eval("1 2 3");
+        assert_eq!(result, Err(ForthError::StackOverflow));
     }
 }
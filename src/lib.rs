@@ -0,0 +1,37 @@
+//! Intérprete del lenguaje Forth.
+//!
+//! Además del binario, la biblioteca permite integrar el intérprete en otros programas:
+//!
+//! ```
+//! use taller_tp_individual::Interpreter;
+//!
+//! let mut forth = Interpreter::new(1024);
+//! forth.eval(": SQUARE DUP * ;").unwrap();
+//! forth.stack_mut().push(7).unwrap();
+//! forth.eval("SQUARE").unwrap();
+//! assert_eq!(forth.stack().to_vec(), &[49]);
+//! ```
+mod cell;
+mod data_space;
+mod error;
+mod input;
+mod interpreter;
+mod output;
+mod stack;
+mod word;
+
+/// Editor de líneas del modo interactivo del binario; no forma parte de la API estable.
+#[doc(hidden)]
+pub mod line_editor;
+/// Modo interactivo del binario; no forma parte de la API estable.
+#[doc(hidden)]
+pub mod repl;
+
+pub use cell::{Cell, CellWidth};
+pub use data_space::DataSpace;
+pub use error::{ForthError, SourceError, Span};
+pub use input::StdinInput;
+pub use interpreter::{DEFAULT_MAX_CALL_DEPTH, DEFAULT_STACK_SIZE_IN_BYTES, Interpreter};
+pub use output::OutputBuffer;
+pub use stack::Stack;
+pub use word::{Body, NativeFn, NativeWord, SelfReference, Word};
@@ -0,0 +1,616 @@
+//! Módulo de edición de líneas para el modo interactivo.
+//!
+//! Mientras se edita una línea, la terminal se pone en modo crudo mediante `tcgetattr`/`tcsetattr` para
+//! recibir las teclas una por una; al terminar la línea se restaura su configuración.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, Read, Write};
+use std::path::PathBuf;
+
+/// Tecla, o combinación de teclas, reconocida por el editor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    Tab,
+    /// Ctrl-C: descarta la línea en edición.
+    Cancel,
+    /// Ctrl-D: termina la entrada si la línea está vacía.
+    Eof,
+    /// Tecla sin acción asociada.
+    Ignored,
+}
+
+/// Texto de la línea en edición junto con la posición del cursor.
+#[derive(Debug, Default)]
+struct LineBuffer {
+    chars: Vec<char>,
+    cursor: usize,
+}
+
+impl LineBuffer {
+    fn text(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    /// Reemplaza el contenido de la línea y ubica el cursor al final.
+    fn set(&mut self, text: &str) {
+        self.chars = text.chars().collect();
+        self.cursor = self.chars.len();
+    }
+
+    fn insert(&mut self, c: char) {
+        self.chars.insert(self.cursor, c);
+        self.cursor += 1;
+    }
+
+    fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.chars.remove(self.cursor);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.cursor < self.chars.len() {
+            self.chars.remove(self.cursor);
+        }
+    }
+
+    fn left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    fn right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.chars.len());
+    }
+
+    /// Completa la word que termina en el cursor con los nombres indicados.
+    ///
+    /// Si hay un único candidato se completa entero; si hay varios, se extiende hasta su
+    /// prefijo común. Devuelve los candidatos encontrados.
+    fn complete(&mut self, words: &[String]) -> Vec<String> {
+        let start = self.chars[..self.cursor]
+            .iter()
+            .rposition(|c| c.is_whitespace())
+            .map_or(0, |i| i + 1);
+        let prefix: String = self.chars[start..self.cursor].iter().collect();
+        if prefix.is_empty() {
+            return Vec::new();
+        }
+
+        let candidates = completions(&prefix, words);
+        let completion = match candidates.as_slice() {
+            [] => return candidates,
+            [single] => format!("{} ", single),
+            _ => common_prefix(&candidates),
+        };
+        for c in completion.chars().skip(prefix.chars().count()) {
+            self.insert(c);
+        }
+        candidates
+    }
+}
+
+/// Devuelve los nombres que comienzan con el prefijo, sin distinguir mayúsculas.
+///
+/// Si el prefijo está escrito en minúsculas, los candidatos también se devuelven en minúsculas.
+fn completions(prefix: &str, words: &[String]) -> Vec<String> {
+    let prefix_upper = prefix.to_uppercase();
+    let lowercase = !prefix.chars().any(char::is_uppercase);
+    words
+        .iter()
+        .filter(|word| word.to_uppercase().starts_with(&prefix_upper))
+        .map(|word| {
+            if lowercase {
+                word.to_lowercase()
+            } else {
+                word.clone()
+            }
+        })
+        .collect()
+}
+
+/// Devuelve el prefijo común más largo de los textos indicados.
+fn common_prefix(texts: &[String]) -> String {
+    let Some((first, rest)) = texts.split_first() else {
+        return String::new();
+    };
+    let mut prefix: Vec<char> = first.chars().collect();
+    for text in rest {
+        let common = prefix
+            .iter()
+            .zip(text.chars())
+            .take_while(|(a, b)| **a == *b)
+            .count();
+        prefix.truncate(common);
+    }
+    prefix.into_iter().collect()
+}
+
+fn read_byte<R: Read>(input: &mut R) -> io::Result<Option<u8>> {
+    let mut byte = [0u8; 1];
+    match input.read(&mut byte)? {
+        0 => Ok(None),
+        _ => Ok(Some(byte[0])),
+    }
+}
+
+/// Lee la siguiente tecla de la entrada. Devuelve `None` si la entrada se terminó.
+fn read_key<R: Read>(input: &mut R) -> io::Result<Option<Key>> {
+    let Some(byte) = read_byte(input)? else {
+        return Ok(None);
+    };
+    let key = match byte {
+        b'\r' | b'\n' => Key::Enter,
+        127 | 8 => Key::Backspace,
+        b'\t' => Key::Tab,
+        1 => Key::Home,
+        2 => Key::Left,
+        3 => Key::Cancel,
+        4 => Key::Eof,
+        5 => Key::End,
+        6 => Key::Right,
+        27 => read_escape_sequence(input)?,
+        b if b < 32 => Key::Ignored,
+        b => Key::Char(read_utf8_char(input, b)?),
+    };
+    Ok(Some(key))
+}
+
+/// Interpreta las secuencias de escape que envían las flechas y las teclas de edición.
+fn read_escape_sequence<R: Read>(input: &mut R) -> io::Result<Key> {
+    if !matches!(read_byte(input)?, Some(b'[') | Some(b'O')) {
+        return Ok(Key::Ignored);
+    }
+    let mut digits = String::new();
+    loop {
+        let Some(byte) = read_byte(input)? else {
+            return Ok(Key::Ignored);
+        };
+        let key = match byte {
+            b'0'..=b'9' => {
+                digits.push(byte as char);
+                continue;
+            }
+            b'A' => Key::Up,
+            b'B' => Key::Down,
+            b'C' => Key::Right,
+            b'D' => Key::Left,
+            b'H' => Key::Home,
+            b'F' => Key::End,
+            b'~' => match digits.as_str() {
+                "1" | "7" => Key::Home,
+                "3" => Key::Delete,
+                "4" | "8" => Key::End,
+                _ => Key::Ignored,
+            },
+            _ => Key::Ignored,
+        };
+        return Ok(key);
+    }
+}
+
+/// Completa la lectura de un carácter UTF-8 a partir de su primer byte.
+fn read_utf8_char<R: Read>(input: &mut R, first: u8) -> io::Result<char> {
+    let len = match first {
+        0xF0.. => 4,
+        0xE0.. => 3,
+        0xC0.. => 2,
+        _ => 1,
+    };
+    let mut bytes = vec![first];
+    for _ in 1..len {
+        match read_byte(input)? {
+            Some(byte) => bytes.push(byte),
+            None => break,
+        }
+    }
+    Ok(std::str::from_utf8(&bytes)
+        .ok()
+        .and_then(|s| s.chars().next())
+        .unwrap_or(char::REPLACEMENT_CHARACTER))
+}
+
+/// Vuelve a dibujar la línea en edición y ubica el cursor.
+fn redraw<W: Write>(output: &mut W, line: &LineBuffer) -> io::Result<()> {
+    write!(output, "\r{}\x1b[K", line.text())?;
+    let back = line.chars.len() - line.cursor;
+    if back > 0 {
+        write!(output, "\x1b[{}D", back)?;
+    }
+    output.flush()
+}
+
+/// Acceso a la configuración de la terminal mediante `termios` de la biblioteca de C, que la
+/// biblioteca estándar ya enlaza.
+///
+/// La estructura y las constantes se declaran a mano, así que solo se usan en las
+/// plataformas para las que se verificaron; en las demás la línea se lee sin edición.
+#[cfg(any(
+    all(
+        target_os = "linux",
+        any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            target_arch = "arm",
+            target_arch = "aarch64"
+        )
+    ),
+    all(
+        target_os = "macos",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    )
+))]
+mod termios {
+    use std::io;
+    use std::mem::MaybeUninit;
+    use std::os::raw::c_int;
+
+    #[cfg(target_os = "linux")]
+    type Flag = std::os::raw::c_uint;
+    #[cfg(target_os = "macos")]
+    type Flag = std::os::raw::c_ulong;
+
+    #[cfg(target_os = "linux")]
+    const NCCS: usize = 32;
+    #[cfg(target_os = "macos")]
+    const NCCS: usize = 20;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct Termios {
+        c_iflag: Flag,
+        c_oflag: Flag,
+        c_cflag: Flag,
+        c_lflag: Flag,
+        #[cfg(target_os = "linux")]
+        c_line: u8,
+        c_cc: [u8; NCCS],
+        c_ispeed: Flag,
+        c_ospeed: Flag,
+    }
+
+    #[cfg(target_os = "linux")]
+    mod consts {
+        use super::Flag;
+        pub const ISIG: Flag = 0o1;
+        pub const ICANON: Flag = 0o2;
+        pub const ECHO: Flag = 0o10;
+        pub const IXON: Flag = 0o2000;
+        pub const VTIME: usize = 5;
+        pub const VMIN: usize = 6;
+    }
+
+    #[cfg(target_os = "macos")]
+    mod consts {
+        use super::Flag;
+        pub const ISIG: Flag = 0x80;
+        pub const ICANON: Flag = 0x100;
+        pub const ECHO: Flag = 0x8;
+        pub const IXON: Flag = 0x200;
+        pub const VMIN: usize = 16;
+        pub const VTIME: usize = 17;
+    }
+
+    use consts::*;
+
+    const TCSANOW: c_int = 0;
+
+    /// Descriptor de archivo de la entrada estándar.
+    const STDIN_FD: c_int = 0;
+
+    unsafe extern "C" {
+        fn tcgetattr(fd: c_int, termios: *mut Termios) -> c_int;
+        fn tcsetattr(fd: c_int, action: c_int, termios: *const Termios) -> c_int;
+    }
+
+    /// Pone la terminal de la entrada estándar en modo crudo y devuelve la configuración
+    /// anterior. Falla si la entrada no es una terminal.
+    pub fn enable_raw_mode() -> io::Result<Termios> {
+        let mut saved = MaybeUninit::<Termios>::uninit();
+        // SAFETY: `tcgetattr` inicializa la estructura cuando devuelve 0.
+        let saved = unsafe {
+            if tcgetattr(STDIN_FD, saved.as_mut_ptr()) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            saved.assume_init()
+        };
+        let mut raw = saved;
+        raw.c_lflag &= !(ICANON | ECHO | ISIG);
+        raw.c_iflag &= !IXON;
+        raw.c_cc[VMIN] = 1;
+        raw.c_cc[VTIME] = 0;
+        // SAFETY: `raw` es una configuración válida obtenida con `tcgetattr`.
+        if unsafe { tcsetattr(STDIN_FD, TCSANOW, &raw) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(saved)
+    }
+
+    /// Restaura una configuración obtenida con `enable_raw_mode`.
+    pub fn restore(saved: &Termios) {
+        // SAFETY: `saved` es una configuración válida obtenida con `tcgetattr`.
+        unsafe {
+            tcsetattr(STDIN_FD, TCSANOW, saved);
+        }
+    }
+}
+
+/// Plataformas sin declaraciones de `termios` verificadas: la terminal nunca se pone en
+/// modo crudo.
+#[cfg(not(any(
+    all(
+        target_os = "linux",
+        any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            target_arch = "arm",
+            target_arch = "aarch64"
+        )
+    ),
+    all(
+        target_os = "macos",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    )
+)))]
+mod termios {
+    use std::io;
+
+    pub struct Termios;
+
+    pub fn enable_raw_mode() -> io::Result<Termios> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+
+    pub fn restore(_: &Termios) {}
+}
+
+/// Mantiene la terminal en modo crudo mientras exista; al descartarse restaura la configuración.
+struct RawMode {
+    saved: termios::Termios,
+}
+
+impl RawMode {
+    /// Pone la terminal de la entrada estándar en modo crudo. Falla si la entrada no es
+    /// una terminal o si la plataforma no lo permite.
+    fn enable() -> io::Result<Self> {
+        termios::enable_raw_mode().map(|saved| RawMode { saved })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        termios::restore(&self.saved);
+    }
+}
+
+/// Editor de líneas con historial y completado de words.
+pub struct LineEditor {
+    history: Vec<String>,
+    /// Archivo en el que se guarda el historial entre sesiones.
+    history_file: Option<PathBuf>,
+}
+
+impl LineEditor {
+    /// Crea un editor cargando el historial guardado en el archivo indicado, si existe.
+    pub fn new(history_file: Option<PathBuf>) -> Self {
+        let history = history_file
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| content.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        LineEditor {
+            history,
+            history_file,
+        }
+    }
+
+    /// Lee una línea de la terminal. Devuelve `None` cuando se termina la entrada.
+    ///
+    /// Las flechas mueven el cursor y recorren el historial, y Tab completa con los
+    /// nombres de `words`. Si no se puede poner la terminal en modo crudo, la línea se
+    /// lee sin edición.
+    pub fn read_line(&mut self, words: &[String]) -> io::Result<Option<String>> {
+        let Ok(_raw_mode) = RawMode::enable() else {
+            let mut line = String::new();
+            if io::stdin().lock().read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            return Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()));
+        };
+        self.edit(&mut io::stdin().lock(), &mut io::stdout().lock(), words)
+    }
+
+    /// Procesa las teclas de la entrada hasta completar una línea.
+    fn edit<R: Read, W: Write>(
+        &mut self,
+        input: &mut R,
+        output: &mut W,
+        words: &[String],
+    ) -> io::Result<Option<String>> {
+        let mut line = LineBuffer::default();
+        let mut history_index = self.history.len();
+        let mut draft = String::new();
+
+        loop {
+            let Some(key) = read_key(input)? else {
+                if line.is_empty() {
+                    return Ok(None);
+                }
+                return Ok(Some(line.text()));
+            };
+            match key {
+                Key::Enter => {
+                    line.cursor = line.chars.len();
+                    redraw(output, &line)?;
+                    // Como en Forth clásico, la salida de la línea continúa tras un espacio.
+                    write!(output, " ")?;
+                    output.flush()?;
+                    let text = line.text();
+                    self.add_history(&text);
+                    return Ok(Some(text));
+                }
+                Key::Eof if line.is_empty() => {
+                    write!(output, "\r\n")?;
+                    return Ok(None);
+                }
+                Key::Eof | Key::Delete => line.delete(),
+                Key::Cancel => {
+                    write!(output, "^C\r\n")?;
+                    line = LineBuffer::default();
+                    history_index = self.history.len();
+                }
+                Key::Up if history_index > 0 => {
+                    if history_index == self.history.len() {
+                        draft = line.text();
+                    }
+                    history_index -= 1;
+                    line.set(&self.history[history_index]);
+                }
+                Key::Down if history_index < self.history.len() => {
+                    history_index += 1;
+                    match self.history.get(history_index) {
+                        Some(entry) => line.set(entry),
+                        None => line.set(&draft),
+                    }
+                }
+                Key::Tab => {
+                    let before = line.chars.len();
+                    let candidates = line.complete(words);
+                    if candidates.len() > 1 && line.chars.len() == before {
+                        write!(output, "\r\n{}\r\n", candidates.join(" "))?;
+                    }
+                }
+                Key::Char(c) => line.insert(c),
+                Key::Backspace => line.backspace(),
+                Key::Left => line.left(),
+                Key::Right => line.right(),
+                Key::Home => line.cursor = 0,
+                Key::End => line.cursor = line.chars.len(),
+                Key::Up | Key::Down | Key::Ignored => {}
+            }
+            redraw(output, &line)?;
+        }
+    }
+
+    /// Agrega una línea al historial y la guarda en el archivo de historial.
+    fn add_history(&mut self, line: &str) {
+        if line.trim().is_empty() || self.history.last().is_some_and(|last| last == line) {
+            return;
+        }
+        self.history.push(line.to_string());
+        if let Some(path) = &self.history_file {
+            // No poder guardar el historial no impide seguir usando el intérprete.
+            let _ = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{}", line));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words() -> Vec<String> {
+        ["DUP", "DROP", "SWAP", "2DUP"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect()
+    }
+
+    fn edit(editor: &mut LineEditor, input: &[u8]) -> Option<String> {
+        let mut output = Vec::new();
+        editor.edit(&mut &input[..], &mut output, &words()).unwrap()
+    }
+
+    #[test]
+    fn test_read_key_sequences() {
+        let mut input: &[u8] = b"a\x1b[A\x1b[D\x1b[3~\x7f\r\xc3\xb1";
+        let mut keys = Vec::new();
+        while let Some(key) = read_key(&mut input).unwrap() {
+            keys.push(key);
+        }
+        assert_eq!(
+            keys,
+            vec![
+                Key::Char('a'),
+                Key::Up,
+                Key::Left,
+                Key::Delete,
+                Key::Backspace,
+                Key::Enter,
+                Key::Char('ñ'),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cursor_editing() {
+        let mut editor = LineEditor::new(None);
+        let line = edit(&mut editor, b"1 3 +\x1b[D\x1b[D\x7f2\x1b[F.\r");
+        assert_eq!(line, Some("1 2 +.".to_string()));
+    }
+
+    #[test]
+    fn test_history_navigation() {
+        let mut editor = LineEditor::new(None);
+        edit(&mut editor, b"1 2\r");
+        edit(&mut editor, b"3 4\r");
+        assert_eq!(
+            edit(&mut editor, b"x\x1b[A\x1b[A\r"),
+            Some("1 2".to_string())
+        );
+        assert_eq!(edit(&mut editor, b"x\x1b[A\x1b[B\r"), Some("x".to_string()));
+    }
+
+    #[test]
+    fn test_tab_completion() {
+        let mut editor = LineEditor::new(None);
+        assert_eq!(edit(&mut editor, b"1 sw\t\r"), Some("1 swap ".to_string()));
+        assert_eq!(edit(&mut editor, b"DR\t\r"), Some("DROP ".to_string()));
+        assert_eq!(edit(&mut editor, b"d\t\r"), Some("d".to_string()));
+    }
+
+    #[test]
+    fn test_common_prefix() {
+        let texts = vec!["dup".to_string(), "drop".to_string()];
+        assert_eq!(common_prefix(&texts), "d");
+        assert_eq!(common_prefix(&[]), "");
+    }
+
+    #[test]
+    fn test_eof_on_empty_line() {
+        let mut editor = LineEditor::new(None);
+        assert_eq!(edit(&mut editor, b"\x04"), None);
+        assert_eq!(edit(&mut editor, b""), None);
+    }
+
+    #[test]
+    fn test_history_file() {
+        let path =
+            std::env::temp_dir().join(format!("{}_line_editor_history_test", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut editor = LineEditor::new(Some(path.clone()));
+        edit(&mut editor, b": sq dup * ;\r");
+        edit(&mut editor, b"\r");
+        let editor = LineEditor::new(Some(path.clone()));
+        assert_eq!(editor.history, vec![": sq dup * ;".to_string()]);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,63 +1,201 @@
 //! Módulo principal de la aplicación Forth.
-mod interpreter;
-mod stack;
-mod word;
-
-use interpreter::Interpreter;
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::thread;
+use taller_tp_individual::line_editor::LineEditor;
+use taller_tp_individual::{
+    CellWidth, DEFAULT_MAX_CALL_DEPTH, DEFAULT_STACK_SIZE_IN_BYTES, Interpreter, repl,
+};
+
+/// Archivo en el que se guarda por defecto el estado final de la pila, relativo al directorio actual.
+const DEFAULT_STACK_FILE: &str = "stack.fth";
+
+/// Opciones de ejecución obtenidas de los argumentos del programa.
+struct Config {
+    /// Archivo a ejecutar; si no se indica, se inicia el modo interactivo.
+    filename: Option<String>,
+    /// Indica si se pidió el modo interactivo con `--repl`.
+    repl: bool,
+    /// Ancho de las celdas, indicado con `--cell-bits=`.
+    cell_width: CellWidth,
+    /// Capacidad, en celdas, de la pila de datos.
+    stack_size: usize,
+    /// Capacidad, en celdas, de la pila de retorno, si se indicó `return-stack-size=`.
+    return_stack_size: Option<usize>,
+    /// Límite de llamadas anidadas, si se indicó `max-call-depth=`.
+    max_call_depth: Option<usize>,
+    /// Indica si se pidió verificar los desbordes aritméticos con `--checked-arithmetic`.
+    checked_arithmetic: bool,
+    /// Indica si se pidió que `AND`, `OR`, `XOR` e `INVERT` operen sobre flags con
+    /// `--logical-flags`.
+    logical_flags: bool,
+    /// Archivo en el que se guarda el estado final de la pila; se indica con `stack-file=`.
+    stack_file: PathBuf,
+    /// Archivo del historial del modo interactivo; por defecto `~/.forth_history`.
+    history_file: Option<PathBuf>,
+}
 
 /// Función principal de la aplicación.
 ///
-/// Si ocurre algún error en cualquiera de estos pasos, se imprime un mensaje de error y se finaliza la ejecución.
+/// Ejecuta el intérprete en un hilo con la pila que necesita el límite de llamadas
+/// anidadas, para que al superarlo se produzca "return-stack-overflow" y no un desborde
+/// de la pila del programa.
 fn main() {
-    let (filename, stack_size) = parse_args();
+    let config = parse_args();
+    let max_call_depth = config.max_call_depth.unwrap_or(DEFAULT_MAX_CALL_DEPTH);
 
-    let code = match read_file(&filename) {
-        Ok(content) => content,
+    let handle = thread::Builder::new()
+        .stack_size(Interpreter::thread_stack_size(max_call_depth))
+        .spawn(move || run(config));
+    match handle {
+        Ok(handle) => {
+            if handle.join().is_err() {
+                std::process::exit(101);
+            }
+        }
         Err(e) => {
-            eprintln!("{}", e);
+            eprintln!("No se pudo iniciar el intérprete: {}", e);
             std::process::exit(1);
         }
-    };
+    }
+}
 
-    let mut interpreter = Interpreter::new(stack_size);
+/// Ejecuta el archivo indicado y, si no se indicó ninguno o se pidió con `--repl`, continúa
+/// en modo interactivo leyendo líneas de la entrada estándar. Si el archivo falla y se
+/// continúa en modo interactivo, se conservan las words que definió.
+/// Si no se puede leer el archivo, se imprime un mensaje de error y se finaliza la ejecución.
+fn run(config: Config) {
+    let mut interpreter = build_interpreter(&config);
 
-    if let Err(e) = interpreter.parse_line(&code) {
-        print!("{}", e);
+    if let Some(filename) = &config.filename {
+        let code = match read_file(filename) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        };
 
-        if e != "stack-overflow" {
-            interpreter = Interpreter::new(stack_size);
+        if let Err(e) = interpreter.run_program(&code) {
+            eprintln!("{}", e.report(filename));
+            if config.repl {
+                println!();
+                interpreter.abort();
+            }
         }
     }
 
-    if let Err(e) = save_stack_to_file(&interpreter, "stack.fth") {
+    if config.repl || config.filename.is_none() {
+        let result = if io::stdin().is_terminal() {
+            let mut editor = LineEditor::new(config.history_file.clone());
+            repl::run_interactive(&mut interpreter, &mut editor)
+        } else {
+            repl::run(&mut interpreter)
+        };
+        if let Err(e) = result {
+            eprintln!("Error al leer la entrada: {}", e);
+        }
+    }
+
+    if let Err(e) = save_stack_to_file(&interpreter, &config.stack_file) {
         eprintln!("Error al guardar el estado de la pila: {}", e);
     }
 }
 
 /// Procesa los argumentos del programa.
-fn parse_args() -> (String, usize) {
+///
+/// El primer argumento que no sea una opción se toma como el archivo a ejecutar.
+/// Si el ancho de celda o el límite de llamadas anidadas indicados no son válidos, se
+/// imprime un mensaje de error y se finaliza la ejecución.
+fn parse_args() -> Config {
     let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Uso: {} archivo.fth [stack-size=N]", args[0]);
-        std::process::exit(1);
-    }
-    let filename = args[1].to_owned();
+    let mut filename = None;
+    let mut repl = false;
+    let mut cell_width = CellWidth::default();
+    let mut checked_arithmetic = false;
+    let mut logical_flags = false;
 
-    let stack_size_in_bytes = if let Some(param) = args.get(2) {
-        if let Some(num_str) = param.strip_prefix("stack-size=") {
-            num_str.parse::<usize>().unwrap_or(128 * 1024)
+    let mut stack_size_in_bytes = DEFAULT_STACK_SIZE_IN_BYTES;
+    let mut return_stack_size_in_bytes = None;
+    let mut max_call_depth = None;
+    let mut stack_file = PathBuf::from(DEFAULT_STACK_FILE);
+    let mut history_file =
+        env::var_os("HOME").map(|home| PathBuf::from(home).join(".forth_history"));
+    for param in &args[1..] {
+        if param == "--repl" {
+            repl = true;
+        } else if param == "--checked-arithmetic" {
+            checked_arithmetic = true;
+        } else if param == "--logical-flags" {
+            logical_flags = true;
+        } else if let Some(bits) = param.strip_prefix("--cell-bits=") {
+            cell_width = match bits.parse().ok().and_then(CellWidth::from_bits) {
+                Some(cell_width) => cell_width,
+                None => {
+                    eprintln!("Ancho de celda inválido: {} (debe ser 16, 32 o 64)", bits);
+                    std::process::exit(1);
+                }
+            };
+        } else if filename.is_none() && !param.contains('=') {
+            filename = Some(param.to_owned());
+        } else if let Some(num_str) = param.strip_prefix("return-stack-size=") {
+            return_stack_size_in_bytes = Some(
+                num_str
+                    .parse::<usize>()
+                    .unwrap_or(DEFAULT_STACK_SIZE_IN_BYTES),
+            );
+        } else if let Some(path) = param.strip_prefix("stack-file=") {
+            stack_file = PathBuf::from(path);
+        } else if let Some(path) = param.strip_prefix("history-file=") {
+            history_file = Some(PathBuf::from(path));
+        } else if let Some(num_str) = param.strip_prefix("max-call-depth=") {
+            match num_str.parse::<usize>() {
+                Ok(depth) => max_call_depth = Some(depth),
+                Err(_) => {
+                    eprintln!("Límite de llamadas anidadas inválido: {}", num_str);
+                    std::process::exit(1);
+                }
+            }
+        } else if let Some(num_str) = param.strip_prefix("stack-size=") {
+            stack_size_in_bytes = num_str
+                .parse::<usize>()
+                .unwrap_or(DEFAULT_STACK_SIZE_IN_BYTES);
         } else {
-            param.parse::<usize>().unwrap_or(128 * 1024)
+            stack_size_in_bytes = param
+                .parse::<usize>()
+                .unwrap_or(DEFAULT_STACK_SIZE_IN_BYTES);
         }
-    } else {
-        128 * 1024
-    };
+    }
 
-    let stack_size = stack_size_in_bytes / std::mem::size_of::<i16>();
-    (filename, stack_size)
+    Config {
+        filename,
+        repl,
+        cell_width,
+        stack_size: stack_size_in_bytes / cell_width.bytes(),
+        return_stack_size: return_stack_size_in_bytes.map(|n| n / cell_width.bytes()),
+        max_call_depth,
+        checked_arithmetic,
+        logical_flags,
+        stack_file,
+        history_file,
+    }
+}
+
+/// Crea el intérprete según la configuración indicada.
+///
+/// Si no se indicó tamaño para la pila de retorno, toma el mismo que la pila de datos.
+fn build_interpreter(config: &Config) -> Interpreter {
+    let return_stack_size = config.return_stack_size.unwrap_or(config.stack_size);
+    let mut interpreter =
+        Interpreter::with_cell_width(config.stack_size, return_stack_size, config.cell_width);
+    if let Some(max_call_depth) = config.max_call_depth {
+        interpreter.set_max_call_depth(max_call_depth);
+    }
+    interpreter.set_checked_arithmetic(config.checked_arithmetic);
+    interpreter.set_logical_flags(config.logical_flags);
+    interpreter
 }
 
 /// Lee el contenido del archivo indicado.
@@ -66,7 +204,7 @@ fn read_file(filename: &str) -> Result<String, String> {
 }
 
 /// Guarda el estado actual de la pila en un archivo.
-fn save_stack_to_file(interpreter: &Interpreter, filename: &str) -> Result<(), String> {
+fn save_stack_to_file(interpreter: &Interpreter, filename: &Path) -> Result<(), String> {
     let stack_vec = interpreter.stack_to_vec();
     let cwd = env::current_dir().map_err(|e| e.to_string())?;
     let file_path: PathBuf = cwd.join(filename);
@@ -0,0 +1,55 @@
+//! Módulo de destinos de salida para el intérprete Forth.
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+/// Destino de salida en memoria que puede compartirse con el intérprete.
+///
+/// Las copias comparten el mismo contenido, de modo que se puede entregar una al
+/// intérprete con `Interpreter::set_output` y leer lo escrito con la otra.
+#[derive(Debug, Clone, Default)]
+pub struct OutputBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl OutputBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Devuelve el texto escrito hasta el momento.
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.borrow()).into_owned()
+    }
+
+    /// Devuelve el texto escrito hasta el momento y vacía el buffer.
+    pub fn take(&self) -> String {
+        let bytes = std::mem::take(&mut *self.0.borrow_mut());
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+impl Write for OutputBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_contents() {
+        let buffer = OutputBuffer::new();
+        let mut writer = buffer.clone();
+        write!(writer, "hola {}", 1).unwrap();
+        assert_eq!(buffer.contents(), "hola 1");
+        assert_eq!(buffer.take(), "hola 1");
+        assert_eq!(buffer.contents(), "");
+    }
+}
@@ -0,0 +1,58 @@
+//! Módulo del modo interactivo (REPL) del intérprete Forth.
+
+use crate::interpreter::Interpreter;
+use crate::line_editor::LineEditor;
+use std::io::{self, Write};
+
+/// Lee líneas de la entrada estándar y las ejecuta en el intérprete hasta que la entrada
+/// se termina.
+///
+/// Tras cada línea se imprime ` ok`, o ` compiled` si queda abierta una definición que
+/// continúa en las líneas siguientes. Ante un error se imprime su mensaje y se descarta el
+/// estado de ejecución, pero se conservan las words definidas hasta el momento.
+///
+/// La entrada estándar se bloquea solo mientras se lee cada línea, así `KEY` y `ACCEPT`
+/// pueden leer de ella las líneas que siguen.
+pub fn run(interpreter: &mut Interpreter) -> io::Result<()> {
+    let mut source = String::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if io::stdin().read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let line = line.strip_suffix('\n').unwrap_or(&line);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        eval_line(interpreter, &mut source, line)?;
+    }
+}
+
+/// Igual que `run`, pero lee las líneas de la terminal con el editor de líneas, que ofrece
+/// historial y completado con las words del diccionario.
+pub fn run_interactive(interpreter: &mut Interpreter, editor: &mut LineEditor) -> io::Result<()> {
+    let mut source = String::new();
+    while let Some(line) = editor.read_line(&interpreter.word_names())? {
+        eval_line(interpreter, &mut source, &line)?;
+    }
+    Ok(())
+}
+
+/// Agrega la línea al código pendiente y lo ejecuta si no deja una definición abierta.
+fn eval_line(interpreter: &mut Interpreter, source: &mut String, line: &str) -> io::Result<()> {
+    source.push_str(line);
+    source.push('\n');
+
+    if Interpreter::is_incomplete(source) {
+        println!(" compiled");
+    } else {
+        match interpreter.parse_source(source) {
+            Ok(()) => println!(" ok"),
+            Err(e) => {
+                println!(" {}", e);
+                interpreter.abort();
+            }
+        }
+        source.clear();
+    }
+    io::stdout().flush()
+}
@@ -1,51 +1,101 @@
 //! Módulo de la pila (Stack) para el intérprete Forth.
 
+use crate::cell::{Cell, CellWidth};
+use crate::error::ForthError;
+
 /// Estructura que representa una pila con capacidad máxima.
+///
+/// Los valores se guardan ajustados al ancho de celda de la pila.
 #[derive(Debug, PartialEq)]
 pub struct Stack {
-    data: Vec<i16>,
+    data: Vec<Cell>,
     max_size: usize,
+    cell: CellWidth,
 }
 
 impl Stack {
-    /// Crea una nueva pila con la capacidad máxima indicada.
+    /// Crea una nueva pila con la capacidad máxima indicada y celdas del ancho por defecto.
     pub fn new(max_size: usize) -> Self {
+        Self::with_cell_width(max_size, CellWidth::default())
+    }
+
+    /// Crea una nueva pila con la capacidad máxima y el ancho de celda indicados.
+    pub fn with_cell_width(max_size: usize, cell: CellWidth) -> Self {
         Stack {
             data: Vec::new(),
             max_size,
+            cell,
         }
     }
 
     /// Empuja un valor en la pila.
     ///
-    /// Si la pila ya alcanzó su capacidad máxima, se retorna un error de "stack-overflow".
-    pub fn push(&mut self, value: i16) -> Result<(), String> {
+    /// Un valor que no entra en una celda se guarda dando la vuelta, como los resultados
+    /// aritméticos. Si la pila ya alcanzó su capacidad máxima, se retorna un error de
+    /// "stack-overflow".
+    pub fn push(&mut self, value: Cell) -> Result<(), ForthError> {
         if self.data.len() >= self.max_size {
-            return Err("stack-overflow".to_string());
+            return Err(ForthError::StackOverflow);
         }
-        self.data.push(value);
+        self.data.push(self.cell.wrap(value));
         Ok(())
     }
 
     /// Saca el valor superior de la pila.
     ///
     /// Si la pila está vacía, se retorna un error de "stack-underflow".
-    pub fn pop(&mut self) -> Result<i16, String> {
-        self.data.pop().ok_or_else(|| "stack-underflow".to_string())
+    pub fn pop(&mut self) -> Result<Cell, ForthError> {
+        self.data.pop().ok_or(ForthError::StackUnderflow)
     }
 
     /// Devuelve el valor superior de la pila sin removerlo.
     ///
     /// Si la pila está vacía, se retorna un error de "stack-underflow".
-    pub fn peek(&self) -> Result<i16, String> {
+    pub fn peek(&self) -> Result<Cell, ForthError> {
+        self.data.last().copied().ok_or(ForthError::StackUnderflow)
+    }
+
+    /// Devuelve la cantidad de elementos en la pila.
+    pub fn depth(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Devuelve la posición en el vector interno del elemento `n` lugares debajo del tope.
+    ///
+    /// Si la pila no tiene tantos elementos, se retorna un error de "stack-underflow".
+    fn index_from_top(&self, n: usize) -> Result<usize, ForthError> {
         self.data
-            .last()
-            .copied()
-            .ok_or_else(|| "stack-underflow".to_string())
+            .len()
+            .checked_sub(n)
+            .and_then(|len| len.checked_sub(1))
+            .ok_or(ForthError::StackUnderflow)
+    }
+
+    /// Devuelve, sin removerlo, el elemento `n` lugares debajo del tope (0 es el tope).
+    ///
+    /// Si la pila no tiene tantos elementos, se retorna un error de "stack-underflow".
+    pub fn pick(&self, n: usize) -> Result<Cell, ForthError> {
+        let index = self.index_from_top(n)?;
+        Ok(self.data[index])
+    }
+
+    /// Mueve al tope el elemento `n` lugares debajo del tope (0 es el tope).
+    ///
+    /// Si la pila no tiene tantos elementos, se retorna un error de "stack-underflow".
+    pub fn roll(&mut self, n: usize) -> Result<(), ForthError> {
+        let index = self.index_from_top(n)?;
+        let value = self.data.remove(index);
+        self.data.push(value);
+        Ok(())
+    }
+
+    /// Descarta los elementos por encima de la profundidad indicada.
+    pub fn truncate(&mut self, depth: usize) {
+        self.data.truncate(depth);
     }
 
     /// Devuelve una referencia al vector interno de datos.
-    pub fn to_vec(&self) -> &[i16] {
+    pub fn to_vec(&self) -> &[Cell] {
         &self.data
     }
 }
@@ -61,7 +111,7 @@ mod tests {
         assert!(stack.push(20).is_ok());
         assert_eq!(stack.pop(), Ok(20));
         assert_eq!(stack.pop(), Ok(10));
-        assert_eq!(stack.pop(), Err("stack-underflow".to_string()));
+        assert_eq!(stack.pop(), Err(ForthError::StackUnderflow));
     }
 
     #[test]
@@ -69,7 +119,48 @@ mod tests {
         let mut stack = Stack::new(2);
         assert!(stack.push(1).is_ok());
         assert!(stack.push(2).is_ok());
-        assert_eq!(stack.push(3), Err("stack-overflow".to_string()));
+        assert_eq!(stack.push(3), Err(ForthError::StackOverflow));
+    }
+
+    #[test]
+    fn test_depth_and_truncate() {
+        let mut stack = Stack::new(10);
+        stack.push(1).unwrap();
+        stack.push(2).unwrap();
+        stack.push(3).unwrap();
+        assert_eq!(stack.depth(), 3);
+        stack.truncate(1);
+        assert_eq!(stack.to_vec(), &[1]);
+    }
+
+    #[test]
+    fn test_pick_and_roll() {
+        let mut stack = Stack::new(10);
+        stack.push(1).unwrap();
+        stack.push(2).unwrap();
+        stack.push(3).unwrap();
+        assert_eq!(stack.pick(0), Ok(3));
+        assert_eq!(stack.pick(2), Ok(1));
+        assert_eq!(stack.pick(3), Err(ForthError::StackUnderflow));
+        assert_eq!(stack.pick(usize::MAX), Err(ForthError::StackUnderflow));
+
+        stack.roll(2).unwrap();
+        assert_eq!(stack.to_vec(), &[2, 3, 1]);
+        stack.roll(0).unwrap();
+        assert_eq!(stack.to_vec(), &[2, 3, 1]);
+        assert_eq!(stack.roll(3), Err(ForthError::StackUnderflow));
+        assert_eq!(stack.to_vec(), &[2, 3, 1]);
+    }
+
+    #[test]
+    fn test_push_wraps_to_cell_width() {
+        let mut stack = Stack::new(10);
+        stack.push(100_000).unwrap();
+        assert_eq!(stack.pop(), Ok(-31072));
+
+        let mut stack = Stack::with_cell_width(10, CellWidth::Bits32);
+        stack.push(100_000).unwrap();
+        assert_eq!(stack.pop(), Ok(100_000));
     }
 
     #[test]
@@ -1,15 +1,181 @@
 //! Módulo para la representación de palabras (words) en el lenguaje Forth.
-use std::rc::Rc;
+use crate::cell::Cell;
+use crate::data_space::DataSpace;
+use crate::error::{ForthError, Span};
+use crate::stack::Stack;
+use std::cell::OnceCell;
+use std::fmt;
+use std::rc::{Rc, Weak};
 
 /// Representa una palabra (word) del lenguaje Forth.
+///
+/// Algunas variantes son detalles de la compilación y pueden agregarse otras en el futuro.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum Word {
-    Number(i16),
-    Words(Vec<Rc<Word>>),
+    Number(Cell),
+    Words(Body),
     Builtin(String),
+    /// Primitiva implementada en Rust y registrada por la aplicación que usa el intérprete.
+    Native(NativeWord),
     StringLiteral(String),
+    /// Word definida con `CREATE` o `VARIABLE`: empuja la dirección de su campo de datos.
+    DataField(usize),
+    /// Word creada con `CREATE` y modificada por `DOES>`: empuja la dirección de su campo
+    /// de datos y luego ejecuta el cuerpo compartido por todas las words del mismo tipo.
+    DataFieldDoes {
+        addr: usize,
+        body: Rc<Word>,
+    },
+    /// Parte de una word definidora que ejecuta `DOES>`: asigna `body` a la última word
+    /// creada con `CREATE` y termina la word definidora.
+    Does(Rc<Word>),
+    /// Word compilada por `POSTPONE` para una word no inmediata: al ejecutarse, agrega la
+    /// word indicada a la definición en curso.
+    Postponed(Rc<Word>),
+    /// Word del compilador (`IF`, `THEN`, `LITERAL`, ...) postergada con `POSTPONE`: al
+    /// ejecutarse, aplica su efecto sobre la definición en curso.
+    PostponedSyntax(String),
+    /// Llamada de una definición a sí misma, compilada por `RECURSE`.
+    Recurse(SelfReference),
+    /// Word definida con `VALUE`: empuja el contenido de la celda en la dirección indicada.
+    Value(usize),
+    /// Asignación compilada por `TO`: guarda el tope de la pila en la celda del `VALUE`.
+    ToValue(usize),
     If {
-        true_branch: Vec<Rc<Word>>,
-        false_branch: Option<Vec<Rc<Word>>>,
+        true_branch: Body,
+        false_branch: Option<Body>,
+    },
+    /// Bucle contado `DO ... LOOP` (o `DO ... +LOOP` si `plus_loop` es verdadero).
+    DoLoop {
+        body: Body,
+        plus_loop: bool,
+    },
+    /// Bucle indefinido `BEGIN ... UNTIL`.
+    BeginUntil {
+        body: Body,
+    },
+    /// Bucle indefinido `BEGIN ... WHILE ... REPEAT`.
+    BeginWhile {
+        condition: Body,
+        body: Body,
     },
+    /// Bucle infinito `BEGIN ... AGAIN`, del que solo se sale con `EXIT`.
+    BeginAgain {
+        body: Body,
+    },
+}
+
+/// Token de la entrada junto con su posición en el código fuente.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Token {
+    pub(crate) text: String,
+    pub(crate) span: Span,
+}
+
+/// Secuencia de words compiladas: el cuerpo de una definición o de una estructura de control.
+///
+/// Junto a cada word se guarda el token que la compiló, para indicar dónde se produjo un
+/// error durante su ejecución.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Body {
+    words: Vec<Rc<Word>>,
+    tokens: Vec<Token>,
+}
+
+impl Body {
+    /// Devuelve las words compiladas, en orden de ejecución.
+    pub fn words(&self) -> &[Rc<Word>] {
+        &self.words
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Devuelve el token que compiló la word en la posición indicada.
+    pub(crate) fn token(&self, index: usize) -> &Token {
+        &self.tokens[index]
+    }
+
+    pub(crate) fn push(&mut self, word: Rc<Word>, token: Token) {
+        self.words.push(word);
+        self.tokens.push(token);
+    }
+
+    pub(crate) fn extend(&mut self, other: Body) {
+        self.words.extend(other.words);
+        self.tokens.extend(other.tokens);
+    }
+
+    /// Separa las words a partir de la posición indicada en un nuevo cuerpo.
+    pub(crate) fn split_off(&mut self, at: usize) -> Body {
+        Body {
+            words: self.words.split_off(at),
+            tokens: self.tokens.split_off(at),
+        }
+    }
+
+    pub(crate) fn into_words(self) -> Vec<Rc<Word>> {
+        self.words
+    }
+}
+
+/// Referencia a una definición que todavía se está compilando.
+///
+/// Se resuelve al finalizar la definición y se guarda como referencia débil para no
+/// formar un ciclo entre la word y su propio cuerpo.
+#[derive(Debug, Clone, Default)]
+pub struct SelfReference(Rc<OnceCell<Weak<Word>>>);
+
+impl SelfReference {
+    /// Asocia la referencia con la word ya compilada.
+    pub fn resolve(&self, word: &Rc<Word>) {
+        let _ = self.0.set(Rc::downgrade(word));
+    }
+
+    /// Devuelve la word referenciada, si la definición ya finalizó y sigue existiendo.
+    pub fn get(&self) -> Option<Rc<Word>> {
+        self.0.get().and_then(Weak::upgrade)
+    }
+}
+
+/// Función que implementa una word nativa: recibe la pila de datos y el espacio de datos.
+pub type NativeFn = dyn Fn(&mut Stack, &mut DataSpace) -> Result<(), ForthError>;
+
+/// Word nativa registrada con `Interpreter::register_native`.
+#[derive(Clone)]
+pub struct NativeWord(Rc<NativeFn>);
+
+impl NativeWord {
+    /// Crea una word nativa a partir de la función indicada.
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&mut Stack, &mut DataSpace) -> Result<(), ForthError> + 'static,
+    {
+        NativeWord(Rc::new(f))
+    }
+
+    /// Ejecuta la función de la word.
+    pub fn call(&self, stack: &mut Stack, data_space: &mut DataSpace) -> Result<(), ForthError> {
+        (self.0)(stack, data_space)
+    }
+}
+
+impl fmt::Debug for NativeWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NativeWord")
+    }
+}
+
+impl PartialEq for NativeWord {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl PartialEq for SelfReference {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
 }
@@ -0,0 +1,222 @@
+//! Lectura de los casos de prueba escritos en YAML (`cases/*.yaml`).
+//!
+//! Se soporta el subconjunto de YAML que usan esos archivos: una lista de mapas con
+//! escalares simples o entre comillas, bloques literales (`|`, `|-`, `|+`), listas en
+//! línea (`[1, 2]`) y comentarios.
+
+use std::fs::{read_dir, read_to_string};
+use std::path::{Path, PathBuf};
+
+/// Caso de prueba: código a ejecutar y resultado esperado.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    pub name: String,
+    pub code: String,
+    /// Salida esperada; si no se indica, no se compara.
+    pub expected_output: Option<String>,
+    pub expected_stack: Vec<i64>,
+    /// Tamaño de la pila en bytes, como el argumento `stack-size=` del binario.
+    pub stack_size: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Scalar(String),
+    List(Vec<String>),
+}
+
+/// Devuelve los archivos `.yaml` del directorio, ordenados por nombre.
+pub fn case_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = read_dir(dir)
+        .expect("No se pudo leer el directorio de casos")
+        .map(|entry| {
+            entry
+                .expect("No se pudo leer el directorio de casos")
+                .path()
+        })
+        .filter(|path| path.extension().is_some_and(|ext| ext == "yaml"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Lee los casos de un archivo YAML.
+pub fn load_cases(path: &Path) -> Result<Vec<Case>, String> {
+    let content = read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    parse_cases(&content).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Interpreta el contenido de un archivo de casos.
+pub fn parse_cases(content: &str) -> Result<Vec<Case>, String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut entries: Vec<Vec<(String, Value)>> = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_start();
+        i += 1;
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let indent = line.len() - trimmed.len();
+        let (rest, key_indent) = match trimmed.strip_prefix("- ") {
+            Some(rest) => {
+                entries.push(Vec::new());
+                (rest.trim_start(), indent + 2)
+            }
+            None => (trimmed, indent),
+        };
+        let entry = entries
+            .last_mut()
+            .ok_or_else(|| format!("línea {}: se esperaba un elemento de la lista", i))?;
+        let (key, value) = rest
+            .split_once(':')
+            .ok_or_else(|| format!("línea {}: se esperaba `clave: valor`", i))?;
+        let value = value.trim();
+
+        let value = match value.strip_prefix('|') {
+            Some(chomping) => {
+                let (text, consumed) = parse_block(&lines[i..], key_indent, chomping);
+                i += consumed;
+                Value::Scalar(text)
+            }
+            None => parse_value(value).map_err(|e| format!("línea {}: {}", i, e))?,
+        };
+        entry.push((key.trim().to_string(), value));
+    }
+
+    entries.into_iter().map(build_case).collect()
+}
+
+/// Lee un bloque literal cuyas líneas están más indentadas que su clave.
+///
+/// Devuelve el texto y la cantidad de líneas consumidas.
+fn parse_block(lines: &[&str], key_indent: usize, chomping: &str) -> (String, usize) {
+    let indent_of = |line: &str| line.len() - line.trim_start().len();
+    let consumed = lines
+        .iter()
+        .position(|line| !line.trim().is_empty() && indent_of(line) <= key_indent)
+        .unwrap_or(lines.len());
+    let block = &lines[..consumed];
+    let block_indent = block
+        .iter()
+        .find(|line| !line.trim().is_empty())
+        .map_or(0, |line| indent_of(line));
+
+    let mut text: String = block
+        .iter()
+        .map(|line| format!("{}\n", line.get(block_indent..).unwrap_or("")))
+        .collect();
+    match chomping.trim() {
+        "-" => text.truncate(text.trim_end_matches('\n').len()),
+        "+" => {}
+        _ => {
+            text.truncate(text.trim_end_matches('\n').len());
+            if !text.is_empty() {
+                text.push('\n');
+            }
+        }
+    }
+    (text, consumed)
+}
+
+fn parse_value(value: &str) -> Result<Value, String> {
+    match value.strip_prefix('[') {
+        Some(rest) => {
+            let items = rest
+                .strip_suffix(']')
+                .ok_or("falta `]` al final de la lista")?;
+            let items = items
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(parse_scalar)
+                .collect::<Result<_, _>>()?;
+            Ok(Value::List(items))
+        }
+        None => parse_scalar(value).map(Value::Scalar),
+    }
+}
+
+fn parse_scalar(value: &str) -> Result<String, String> {
+    if let Some(rest) = value.strip_prefix('"') {
+        return parse_double_quoted(rest);
+    }
+    if let Some(rest) = value.strip_prefix('\'') {
+        return parse_single_quoted(rest);
+    }
+    let plain = match value.find(" #") {
+        Some(comment) => &value[..comment],
+        None => value,
+    };
+    Ok(plain.trim().to_string())
+}
+
+fn parse_single_quoted(rest: &str) -> Result<String, String> {
+    let mut text = String::new();
+    let mut chars = rest.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                text.push('\'');
+            }
+            '\'' => return Ok(text),
+            c => text.push(c),
+        }
+    }
+    Err("falta la comilla de cierre".to_string())
+}
+
+fn parse_double_quoted(rest: &str) -> Result<String, String> {
+    let mut text = String::new();
+    let mut chars = rest.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Ok(text),
+            '\\' => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some('t') => text.push('\t'),
+                Some('r') => text.push('\r'),
+                Some('0') => text.push('\0'),
+                Some(c @ ('"' | '\\' | '/')) => text.push(c),
+                other => return Err(format!("escape inválido: \\{:?}", other)),
+            },
+            c => text.push(c),
+        }
+    }
+    Err("falta la comilla de cierre".to_string())
+}
+
+fn build_case(entry: Vec<(String, Value)>) -> Result<Case, String> {
+    let mut case = Case {
+        name: String::new(),
+        code: String::new(),
+        expected_output: None,
+        expected_stack: Vec::new(),
+        stack_size: None,
+    };
+    for (key, value) in entry {
+        match (key.as_str(), value) {
+            ("name", Value::Scalar(name)) => case.name = name,
+            ("code", Value::Scalar(code)) => case.code = code,
+            ("expected_output", Value::Scalar(output)) => case.expected_output = Some(output),
+            ("expected_stack", Value::List(items)) => {
+                case.expected_stack = items
+                    .iter()
+                    .map(|item| item.parse::<i64>().map_err(|e| format!("{}: {}", item, e)))
+                    .collect::<Result<_, _>>()?;
+            }
+            ("stack_size", Value::Scalar(size)) => {
+                case.stack_size = Some(size.parse().map_err(|e| format!("{}: {}", size, e))?);
+            }
+            (key, value) => return Err(format!("clave inesperada `{}`: {:?}", key, value)),
+        }
+    }
+    if case.name.is_empty() {
+        return Err(format!("caso sin nombre: {:?}", case.code));
+    }
+    Ok(case)
+}
@@ -1,15 +1,17 @@
 #![allow(dead_code)]
+pub mod cases;
+
 use std::env;
 use std::fs::{File, read_to_string, remove_file};
 use std::io::Write;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 pub fn run_test_case_stdout_with_stack_size(
     test_name: &str,
     code: &str,
     expected_output: &str,
-    expected_stack: &[i16],
+    expected_stack: &[i64],
     stack_size: Option<usize>,
 ) {
     let filename = format!("{}.fth", test_name.replace(' ', "_"));
@@ -25,7 +27,7 @@ pub fn run_test_case_stdout_with_stack_size(
         test_name
     );
 
-    let output_lines = read_stack_output();
+    let output_lines = read_stack_output(&temp_file);
     let expected_lines: Vec<String> = expected_stack.iter().map(|n| n.to_string()).collect();
     assert_eq!(
         output_lines, expected_lines,
@@ -34,11 +36,22 @@ pub fn run_test_case_stdout_with_stack_size(
     );
 
     cleanup_temp_file(&temp_file);
-    remove_file("stack.fth").expect("No se pudo borrar stack.fth");
 }
 
-fn read_stack_output() -> Vec<String> {
-    let stack_output = read_to_string("stack.fth").expect("No se pudo leer el archivo stack.fth");
+/// Devuelve la ruta en la que el binario guarda la pila al ejecutar el archivo indicado.
+///
+/// Cada test usa su propio archivo para que los tests puedan correr en paralelo.
+pub fn stack_file_path(file_path: &Path) -> PathBuf {
+    file_path.with_extension("stack.fth")
+}
+
+pub fn stack_file_arg(file_path: &Path) -> String {
+    format!("stack-file={}", stack_file_path(file_path).display())
+}
+
+pub fn read_stack_output(file_path: &Path) -> Vec<String> {
+    let stack_output =
+        read_to_string(stack_file_path(file_path)).expect("No se pudo leer el archivo de la pila");
     stack_output
         .lines()
         .map(|l| l.trim().to_string())
@@ -46,9 +59,13 @@ fn read_stack_output() -> Vec<String> {
         .collect()
 }
 
+/// Devuelve una ruta en el directorio temporal propia del proceso de tests actual.
+pub fn temp_path(filename: &str) -> PathBuf {
+    env::temp_dir().join(format!("{}_{}", std::process::id(), filename))
+}
+
 pub fn create_temp_file(filename: &str, content: &str) -> PathBuf {
-    let mut temp_path: PathBuf = env::temp_dir();
-    temp_path.push(filename);
+    let temp_path = temp_path(filename);
     let mut file = File::create(&temp_path).expect("No se pudo crear el archivo temporal");
     writeln!(file, "{}", content).expect("No se pudo escribir en el archivo temporal");
     temp_path
@@ -58,18 +75,32 @@ pub fn run_binary_with_file(file_path: &PathBuf) -> String {
     let bin_path = env!("CARGO_BIN_EXE_taller_tp_individual");
     let output = Command::new(bin_path)
         .arg(file_path)
+        .arg(stack_file_arg(file_path))
         .output()
         .expect("Fallo al ejecutar el comando");
     String::from_utf8_lossy(&output.stdout).to_string()
 }
 
+pub fn run_binary_with_file_output(file_path: &PathBuf) -> (String, String) {
+    let bin_path = env!("CARGO_BIN_EXE_taller_tp_individual");
+    let output = Command::new(bin_path)
+        .arg(file_path)
+        .arg(stack_file_arg(file_path))
+        .output()
+        .expect("Fallo al ejecutar el comando");
+    (
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+    )
+}
+
 pub fn run_binary_with_file_and_stack_size(
     file_path: &PathBuf,
     stack_size: Option<usize>,
 ) -> String {
     let bin_path = env!("CARGO_BIN_EXE_taller_tp_individual");
     let mut cmd = Command::new(bin_path);
-    cmd.arg(file_path);
+    cmd.arg(file_path).arg(stack_file_arg(file_path));
     if let Some(size) = stack_size {
         cmd.arg(format!("stack-size={}", size));
     }
@@ -80,7 +111,7 @@ pub fn run_binary_with_file_and_stack_size(
 pub fn run_binary_with_file_args(file_path: &PathBuf, extra_args: &[&str]) -> String {
     let bin_path = env!("CARGO_BIN_EXE_taller_tp_individual");
     let mut cmd = Command::new(bin_path);
-    cmd.arg(file_path);
+    cmd.arg(file_path).arg(stack_file_arg(file_path));
     for arg in extra_args {
         cmd.arg(arg);
     }
@@ -88,15 +119,43 @@ pub fn run_binary_with_file_args(file_path: &PathBuf, extra_args: &[&str]) -> St
     String::from_utf8_lossy(&output.stdout).to_string()
 }
 
+/// Ejecuta el binario escribiendo `input` en su entrada estándar.
+///
+/// Devuelve la salida estándar y el contenido del archivo de la pila, que luego se borra.
+pub fn run_binary_with_stdin(test_name: &str, args: &[&str], input: &str) -> (String, String) {
+    let bin_path = env!("CARGO_BIN_EXE_taller_tp_individual");
+    let stack_file = temp_path(&format!("{}.stack.fth", test_name.replace(' ', "_")));
+    let mut child = Command::new(bin_path)
+        .args(args)
+        .arg(format!("stack-file={}", stack_file.display()))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Fallo al ejecutar el comando");
+    child
+        .stdin
+        .take()
+        .expect("No se pudo abrir la entrada estándar")
+        .write_all(input.as_bytes())
+        .expect("No se pudo escribir en la entrada estándar");
+    let output = child
+        .wait_with_output()
+        .expect("Fallo al esperar el comando");
+    let stack = read_to_string(&stack_file).expect("No se pudo leer el archivo de la pila");
+    remove_file(&stack_file).expect("No se pudo borrar el archivo de la pila");
+    (String::from_utf8_lossy(&output.stdout).to_string(), stack)
+}
+
 pub fn cleanup_temp_file(file_path: &PathBuf) {
     remove_file(file_path).expect("No se pudo borrar el archivo temporal");
+    let _ = remove_file(stack_file_path(file_path));
 }
 
-pub fn run_test_case(test_name: &str, code: &str, expected_stack: &[i16]) {
+pub fn run_test_case(test_name: &str, code: &str, expected_stack: &[i64]) {
     let filename = format!("{}.fth", test_name.replace(' ', "_"));
     let temp_file = create_temp_file(&filename, code);
     let _ = run_binary_with_file(&temp_file);
-    let output_lines = read_stack_output();
+    let output_lines = read_stack_output(&temp_file);
     let expected_lines: Vec<String> = expected_stack.iter().map(|n| n.to_string()).collect();
     assert_eq!(
         output_lines, expected_lines,
@@ -104,14 +163,13 @@ pub fn run_test_case(test_name: &str, code: &str, expected_stack: &[i16]) {
         test_name
     );
     cleanup_temp_file(&temp_file);
-    remove_file("stack.fth").expect("No se pudo borrar stack.fth");
 }
 
 pub fn run_test_case_stdout(
     test_name: &str,
     code: &str,
     expected_output: &str,
-    expected_stack: &[i16],
+    expected_stack: &[i64],
 ) {
     let filename = format!("{}.fth", test_name.replace(' ', "_"));
     let temp_file = create_temp_file(&filename, code);
@@ -135,7 +193,7 @@ pub fn run_test_case_stdout(
         test_name
     );
 
-    let output_lines = read_stack_output();
+    let output_lines = read_stack_output(&temp_file);
     let expected_lines: Vec<String> = expected_stack.iter().map(|n| n.to_string()).collect();
     assert_eq!(
         output_lines, expected_lines,
@@ -144,5 +202,4 @@ pub fn run_test_case_stdout(
     );
 
     cleanup_temp_file(&temp_file);
-    remove_file("stack.fth").expect("No se pudo borrar stack.fth");
 }
@@ -0,0 +1,103 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use taller_tp_individual::{ForthError, Interpreter, OutputBuffer, Span, Word};
+
+#[test]
+fn test_eval_keeps_state_between_calls() {
+    let mut forth = Interpreter::new(64);
+    forth.eval(": double 2 * ;").unwrap();
+    forth.eval("21 double").unwrap();
+    assert_eq!(forth.stack_to_vec(), vec![42]);
+}
+
+#[test]
+fn test_stack_accessors() {
+    let mut forth = Interpreter::new(64);
+    forth.stack_mut().push(3).unwrap();
+    forth.stack_mut().push(4).unwrap();
+    forth.eval("+").unwrap();
+    assert_eq!(forth.stack().depth(), 1);
+    assert_eq!(forth.stack_mut().pop(), Ok(7));
+    assert_eq!(forth.return_stack().depth(), 0);
+}
+
+#[test]
+fn test_eval_errors() {
+    let mut forth = Interpreter::new(64);
+    assert_eq!(forth.eval("drop"), Err(ForthError::StackUnderflow));
+    assert_eq!(
+        forth.eval("1 2\nfoo"),
+        Err(ForthError::UnknownWord("foo".to_string()))
+    );
+
+    let error = forth.parse_source("1 2\n  foo").unwrap_err();
+    assert_eq!(error.span, Span { line: 2, column: 3 });
+}
+
+#[test]
+fn test_word_lookup() {
+    let mut forth = Interpreter::new(64);
+    forth.eval("5 constant five").unwrap();
+    assert!(forth.word("FIVE").is_some());
+    assert_eq!(
+        forth.word("dup").as_deref(),
+        Some(&Word::Builtin("DUP".to_string()))
+    );
+    assert!(forth.word("nothing").is_none());
+    assert!(forth.word_names().contains(&"FIVE".to_string()));
+}
+
+#[test]
+fn test_register_native_word() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut forth = Interpreter::new(64);
+    let sink = Rc::clone(&log);
+    forth.register_native("record", move |stack, _| {
+        sink.borrow_mut().push(stack.pop()?);
+        Ok(())
+    });
+    forth
+        .eval(": record-twice dup record record ; 5 record-twice")
+        .unwrap();
+    assert_eq!(*log.borrow(), vec![5, 5]);
+    assert_eq!(forth.eval("record"), Err(ForthError::StackUnderflow));
+}
+
+#[test]
+fn test_output_is_captured_in_process() {
+    let output = OutputBuffer::new();
+    let mut forth = Interpreter::new(64);
+    forth.set_output(output.clone());
+    forth
+        .eval(": countdown 0 3 do i . -1 +loop ; countdown")
+        .unwrap();
+    forth.eval("cr .\" listo\"").unwrap();
+    assert_eq!(output.contents(), "3 2 1 0 \nlisto");
+}
+
+#[test]
+fn test_host_values_wrap_to_cell_width() {
+    let mut forth = Interpreter::new(64);
+    forth.register_native("square", |stack, _| {
+        let n = stack.pop()?;
+        stack.push(n * n)
+    });
+    forth.stack_mut().push(100_000).unwrap();
+    forth.eval("300 square").unwrap();
+    assert_eq!(forth.stack_to_vec(), vec![-31072, 24464]);
+}
+
+#[test]
+fn test_run_program_error_policy() {
+    let output = OutputBuffer::new();
+    let mut forth = Interpreter::new(3);
+    forth.set_output(output.clone());
+    let error = forth.run_program(": sq dup * ;\n1 2 0 /").unwrap_err();
+    assert_eq!(error.error, ForthError::DivisionByZero);
+    assert_eq!(output.take(), "division-by-zero");
+    assert_eq!(forth.stack_to_vec(), Vec::<i64>::new());
+
+    forth.run_program("2 sq 1 2 3").unwrap_err();
+    assert_eq!(output.take(), "stack-overflow");
+    assert_eq!(forth.stack_to_vec(), vec![4, 1, 2]);
+}
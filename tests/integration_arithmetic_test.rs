@@ -0,0 +1,56 @@
+mod common;
+
+use common::run_test_case;
+
+#[test]
+fn test_mod() {
+    run_test_case("mod", "7 3 MOD -7 3 MOD", &[1, -1]);
+}
+
+#[test]
+fn test_slash_mod() {
+    run_test_case("slash mod", "7 3 /MOD -7 2 /MOD", &[1, 2, -1, -3]);
+}
+
+#[test]
+fn test_star_slash() {
+    run_test_case("star slash", "10000 3 4 */", &[7500]);
+}
+
+#[test]
+fn test_star_slash_mod() {
+    run_test_case("star slash mod", "1000 100 7 */MOD", &[5, 14285]);
+}
+
+#[test]
+fn test_floored_and_symmetric_division() {
+    run_test_case(
+        "floored and symmetric division",
+        "-7 -1 2 FM/MOD -7 -1 2 SM/REM",
+        &[1, -4, -1, -3],
+    );
+}
+
+#[test]
+fn test_double_dividend() {
+    run_test_case("double dividend", "0 1 4 SM/REM", &[0, 16384]);
+}
+
+#[test]
+fn test_negate_and_abs() {
+    run_test_case("negate and abs", "5 NEGATE -3 ABS 0 NEGATE", &[-5, 3, 0]);
+}
+
+#[test]
+fn test_min_max() {
+    run_test_case("min max", "3 -2 MIN 3 -2 MAX", &[-2, 3]);
+}
+
+#[test]
+fn test_increment_and_shifts() {
+    run_test_case(
+        "increment and shifts",
+        "1 1+ 1 1- 3 2* -7 2/",
+        &[2, 0, 6, -4],
+    );
+}
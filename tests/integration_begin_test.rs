@@ -0,0 +1,53 @@
+mod common;
+
+use common::run_test_case;
+
+#[test]
+fn test_begin_until() {
+    run_test_case(
+        "begin until",
+        ": countdown begin dup 1 - dup 0 = until ;\n3 countdown",
+        &[3, 2, 1, 0],
+    );
+}
+
+#[test]
+fn test_begin_until_top_level() {
+    run_test_case("begin until top level", "0 begin 1 + dup 5 = until", &[5]);
+}
+
+#[test]
+fn test_begin_while_repeat() {
+    run_test_case(
+        "begin while repeat",
+        ": f begin dup 0 > while dup 1 - repeat ;\n3 f",
+        &[3, 2, 1, 0],
+    );
+}
+
+#[test]
+fn test_begin_while_repeat_never_runs() {
+    run_test_case(
+        "begin while repeat never runs",
+        ": f begin dup 0 > while 99 repeat ;\n0 f",
+        &[0],
+    );
+}
+
+#[test]
+fn test_begin_again_exit() {
+    run_test_case(
+        "begin again exit",
+        ": f begin 1 + dup 4 = if exit then again ;\n0 f",
+        &[4],
+    );
+}
+
+#[test]
+fn test_begin_inside_do_loop() {
+    run_test_case(
+        "begin inside do loop",
+        ": f 3 0 do 0 begin 1 + dup i > until loop ;\nf",
+        &[1, 2, 3],
+    );
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::run_test_case;
+use common::{cleanup_temp_file, create_temp_file, run_binary_with_file_args};
+
+#[test]
+fn test_bitwise_and_or_xor() {
+    run_test_case(
+        "bitwise and or xor",
+        "12 10 AND 12 10 OR 12 10 XOR",
+        &[8, 14, 6],
+    );
+}
+
+#[test]
+fn test_invert() {
+    run_test_case("invert", "0 INVERT 5 INVERT", &[-1, -6]);
+}
+
+#[test]
+fn test_shifts() {
+    run_test_case(
+        "shifts",
+        "1 3 LSHIFT 256 4 RSHIFT -1 15 RSHIFT",
+        &[8, 16, 1],
+    );
+}
+
+#[test]
+fn test_logical_flags() {
+    let temp_file = create_temp_file(
+        "test_logical_flags.fth",
+        "12 10 AND . 12 10 XOR . 5 INVERT .",
+    );
+    let output = run_binary_with_file_args(&temp_file, &["--logical-flags"]);
+    assert_eq!(output, "-1 0 0 ");
+    cleanup_temp_file(&temp_file);
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use common::cases::{Case, case_files, load_cases, parse_cases};
+use std::path::Path;
+use taller_tp_individual::{CellWidth, DEFAULT_STACK_SIZE_IN_BYTES, Interpreter, OutputBuffer};
+
+/// Normaliza la salida ignorando los espacios al final de cada línea.
+fn normalize(output: &str) -> String {
+    output
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Ejecuta un caso en el mismo proceso, del mismo modo que lo hace el binario.
+fn run_case(case: &Case) -> Result<(), String> {
+    let stack_size = case.stack_size.unwrap_or(DEFAULT_STACK_SIZE_IN_BYTES);
+    let mut interpreter = Interpreter::new(stack_size / CellWidth::default().bytes());
+    let output = OutputBuffer::new();
+    interpreter.set_output(output.clone());
+
+    let _ = interpreter.run_program(&case.code);
+    let stack = interpreter.stack_to_vec();
+
+    if let Some(expected_output) = &case.expected_output {
+        let actual = normalize(&output.contents());
+        if actual != normalize(expected_output) {
+            return Err(format!(
+                "salida {:?}, se esperaba {:?}",
+                actual, expected_output
+            ));
+        }
+    }
+    if stack != case.expected_stack {
+        return Err(format!(
+            "pila {:?}, se esperaba {:?}",
+            stack, case.expected_stack
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_yaml_cases() {
+    let cases_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("cases");
+    let mut total = 0;
+    let mut failures = Vec::new();
+
+    for path in case_files(&cases_dir) {
+        let cases = load_cases(&path).unwrap_or_else(|e| panic!("{}", e));
+        let file = path.file_name().unwrap_or_default().to_string_lossy();
+        for case in cases {
+            total += 1;
+            if let Err(e) = run_case(&case) {
+                failures.push(format!("{} / {}: {}", file, case.name, e));
+            }
+        }
+    }
+
+    assert!(
+        total > 0,
+        "No se encontraron casos en {}",
+        cases_dir.display()
+    );
+    assert!(
+        failures.is_empty(),
+        "Fallaron {} de {} casos:\n{}",
+        failures.len(),
+        total,
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_parse_cases() {
+    let content = r#"
+# comentario
+- name: "con escapes\t"
+  stack_size: 10 # en bytes
+  code: |
+    1 2
+      3
+
+  expected_output: "a\n\"b\""
+  expected_stack: [1, -2]
+
+- name: 'it''s'
+  code: |-
+    dup
+  expected_stack: []
+"#;
+    let cases = parse_cases(content).unwrap();
+    assert_eq!(
+        cases,
+        vec![
+            Case {
+                name: "con escapes\t".to_string(),
+                code: "1 2\n  3\n".to_string(),
+                expected_output: Some("a\n\"b\"".to_string()),
+                expected_stack: vec![1, -2],
+                stack_size: Some(10),
+            },
+            Case {
+                name: "it's".to_string(),
+                code: "dup".to_string(),
+                expected_output: None,
+                expected_stack: vec![],
+                stack_size: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parse_cases_errors() {
+    assert!(parse_cases("- name: x\n  unknown: 1").is_err());
+    assert!(parse_cases("- name: \"x").is_err());
+    assert!(parse_cases("- name: x\n  expected_stack: [a]").is_err());
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::cleanup_temp_file;
+use common::create_temp_file;
+use common::run_binary_with_file_args;
+use common::run_test_case;
+
+fn run_with_args(test_name: &str, code: &str, args: &[&str]) -> String {
+    let temp_file = create_temp_file(&format!("{}.fth", test_name), code);
+    let output = run_binary_with_file_args(&temp_file, args);
+    cleanup_temp_file(&temp_file);
+    output
+}
+
+#[test]
+fn test_default_cells_wrap_at_16_bits() {
+    run_test_case("default cells wrap", "32767 1 +", &[-32768]);
+}
+
+#[test]
+fn test_number_too_big_for_16_bits() {
+    let output = run_with_args("number too big for 16 bits", "40000", &[]);
+    assert_eq!(output.trim(), "?", "Salida: {:?}", output);
+}
+
+#[test]
+fn test_32_bit_cells() {
+    let output = run_with_args(
+        "32 bit cells",
+        "40000 2 * . 2147483647 1 + . 1 CELLS .",
+        &["--cell-bits=32"],
+    );
+    assert_eq!(output.trim(), "80000 -2147483648 4", "Salida: {:?}", output);
+}
+
+#[test]
+fn test_64_bit_cells() {
+    let output = run_with_args(
+        "64 bit cells",
+        "VARIABLE X 5000000000 X ! X @ . 1 CELLS .",
+        &["--cell-bits=64"],
+    );
+    assert_eq!(output.trim(), "5000000000 8", "Salida: {:?}", output);
+}
+
+#[test]
+fn test_stack_size_counts_cell_bytes() {
+    let output = run_with_args(
+        "stack size counts cell bytes",
+        "1 2 3",
+        &["stack-size=8", "--cell-bits=32"],
+    );
+    assert_eq!(output.trim(), "stack-overflow", "Salida: {:?}", output);
+
+    let output = run_with_args(
+        "stack size with 16 bit cells",
+        "1 2 3 4 . . . .",
+        &["stack-size=8"],
+    );
+    assert_eq!(output.trim(), "4 3 2 1", "Salida: {:?}", output);
+}
+
+#[test]
+fn test_64_bit_address_out_of_range() {
+    let output = run_with_args("64 bit address out of range", "-1 @", &["--cell-bits=64"]);
+    assert_eq!(
+        output.trim(),
+        "address-out-of-range",
+        "Salida: {:?}",
+        output
+    );
+
+    let output = run_with_args("64 bit store out of range", "1 -1 !", &["--cell-bits=64"]);
+    assert_eq!(
+        output.trim(),
+        "address-out-of-range",
+        "Salida: {:?}",
+        output
+    );
+}
+
+#[test]
+fn test_64_bit_double_division_overflow() {
+    let code = "0 -9223372036854775808 -1 FM/MOD . .";
+    let output = run_with_args("64 bit double division wraps", code, &["--cell-bits=64"]);
+    assert_eq!(output.trim(), "0 0", "Salida: {:?}", output);
+
+    let output = run_with_args(
+        "64 bit double division checked",
+        code,
+        &["--cell-bits=64", "--checked-arithmetic"],
+    );
+    assert_eq!(output.trim(), "arithmetic-overflow", "Salida: {:?}", output);
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use common::run_test_case;
+
+#[test]
+fn test_not_equals() {
+    run_test_case("not equals", "1 2 <> 2 2 <>", &[-1, 0]);
+}
+
+#[test]
+fn test_less_or_greater_equals() {
+    run_test_case(
+        "less or greater equals",
+        "2 2 <= 3 2 <= 2 2 >= 1 2 >=",
+        &[-1, 0, -1, 0],
+    );
+}
+
+#[test]
+fn test_zero_comparisons() {
+    run_test_case(
+        "zero comparisons",
+        "0 0= 3 0= -3 0< 3 0< 3 0> 0 0>",
+        &[-1, 0, -1, 0, -1, 0],
+    );
+}
+
+#[test]
+fn test_unsigned_comparisons() {
+    run_test_case(
+        "unsigned comparisons",
+        "1 -1 U< -1 1 U< -1 1 U> 1 2 U>",
+        &[-1, 0, -1, 0],
+    );
+}
+
+#[test]
+fn test_within() {
+    run_test_case(
+        "within",
+        "5 1 10 WITHIN 10 1 10 WITHIN 1 1 10 WITHIN -1 1 10 WITHIN",
+        &[-1, 0, -1, 0],
+    );
+}
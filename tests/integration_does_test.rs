@@ -0,0 +1,48 @@
+mod common;
+
+use common::run_test_case;
+
+#[test]
+fn test_does_constant() {
+    run_test_case(
+        "does constant",
+        ": const create , does> @ ;\n42 const answer\nanswer answer",
+        &[42, 42],
+    );
+}
+
+#[test]
+fn test_does_array() {
+    run_test_case(
+        "does array",
+        "\
+: array create cells allot does> swap cells + ;
+3 array a
+5 array b
+10 0 a ! 20 2 a ! 30 0 b !
+0 a @ 2 a @ 0 b @",
+        &[10, 20, 30],
+    );
+}
+
+#[test]
+fn test_does_enum_generator() {
+    run_test_case(
+        "does enum generator",
+        "\
+variable next
+: enum create next @ , 1 next +! does> @ ;
+enum red enum green enum blue
+blue red green",
+        &[2, 0, 1],
+    );
+}
+
+#[test]
+fn test_does_with_code_after_create() {
+    run_test_case(
+        "does with code after create",
+        ": counter create 0 , does> dup @ 1 + dup rot ! ;\ncounter c\nc c c",
+        &[1, 2, 3],
+    );
+}
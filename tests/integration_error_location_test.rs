@@ -0,0 +1,23 @@
+mod common;
+
+use common::cleanup_temp_file;
+use common::create_temp_file;
+use common::run_binary_with_file_output;
+
+#[test]
+fn test_error_location_on_stderr() {
+    let temp_file = create_temp_file(
+        "error_location.fth",
+        ": inner 1 0 / ;\n: outer\n  inner ;\n5 outer",
+    );
+    let (stdout, stderr) = run_binary_with_file_output(&temp_file);
+    assert_eq!(stdout, "division-by-zero");
+    assert_eq!(
+        stderr.trim_end(),
+        format!(
+            "{}:1:13: division-by-zero en `/`\n    en INNER\n    en OUTER",
+            temp_file.display()
+        )
+    );
+    cleanup_temp_file(&temp_file);
+}
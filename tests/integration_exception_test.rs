@@ -0,0 +1,97 @@
+mod common;
+
+use common::run_test_case;
+use common::run_test_case_stdout;
+
+#[test]
+fn test_catch_no_error() {
+    run_test_case("catch no error", ": add ['] + catch ;\n2 3 add", &[5, 0]);
+}
+
+#[test]
+fn test_catch_division_by_zero() {
+    run_test_case(
+        "catch division by zero",
+        ": safe-div ['] / catch ;\n10 0 safe-div",
+        &[10, 0, -10],
+    );
+}
+
+#[test]
+fn test_catch_stack_underflow() {
+    run_test_case(
+        "catch stack underflow",
+        ": f drop drop drop ;\n1 ' f catch",
+        &[1, -4],
+    );
+}
+
+#[test]
+fn test_catch_undefined_word() {
+    run_test_case(
+        "catch undefined word",
+        ": tick ' ;\n' tick catch undefined-word",
+        &[-13],
+    );
+}
+
+#[test]
+fn test_catch_stack_overflow() {
+    run_test_case(
+        "catch stack overflow",
+        ": fill begin 1 again ;\n' fill catch",
+        &[-3],
+    );
+}
+
+#[test]
+fn test_throw_user_code() {
+    run_test_case(
+        "throw user code",
+        ": check dup 0 < if 99 throw then ;\n: run ['] check catch ;\n5 run -5 run",
+        &[5, 0, -5, 99],
+    );
+}
+
+#[test]
+fn test_throw_zero() {
+    run_test_case("throw zero", "1 0 throw", &[1]);
+}
+
+#[test]
+fn test_catch_restores_return_stack() {
+    run_test_case(
+        "catch restores return stack",
+        ": f 1 >r 2 >r -1 throw ;\n5 >r ' f catch r>",
+        &[-1, 5],
+    );
+}
+
+#[test]
+fn test_uncaught_throw_standard_code() {
+    run_test_case_stdout(
+        "uncaught throw standard code",
+        "-4 throw",
+        "stack-underflow\n",
+        &[],
+    );
+}
+
+#[test]
+fn test_uncaught_throw_user_code() {
+    run_test_case_stdout("uncaught throw user code", "7 throw", "exception 7\n", &[]);
+}
+
+#[test]
+fn test_catch_restores_return_stack_contents() {
+    run_test_case(
+        "catch restores return stack after 2r@",
+        "7 >r ' 2r@ catch r>",
+        &[-6, 7],
+    );
+    run_test_case(
+        "catch restores replaced return stack cell",
+        ": f r> drop 9 >r 1 0 / ;\n5 >r ' f catch r>",
+        &[-10, 5],
+    );
+}
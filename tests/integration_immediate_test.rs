@@ -0,0 +1,115 @@
+mod common;
+
+use common::run_test_case;
+use common::run_test_case_stdout;
+
+#[test]
+fn test_immediate_word_runs_at_compile_time() {
+    run_test_case(
+        "immediate word runs at compile time",
+        ": five 5 ; immediate\n: f five ;\nf",
+        &[5],
+    );
+}
+
+#[test]
+fn test_postpone_non_immediate() {
+    run_test_case(
+        "postpone non immediate",
+        ": compile-dup postpone dup ; immediate\n: double compile-dup + ;\n4 double",
+        &[8],
+    );
+}
+
+#[test]
+fn test_postpone_control_structure() {
+    run_test_case(
+        "postpone control structure",
+        "\
+: unless postpone not postpone if ; immediate
+: check unless 1 else 2 then ;
+0 check
+-1 check",
+        &[1, 2],
+    );
+}
+
+#[test]
+fn test_user_defined_loop_syntax() {
+    run_test_case(
+        "user defined loop syntax",
+        "\
+: times 0 postpone literal postpone do ; immediate
+: stars 3 times 42 loop ;
+stars",
+        &[42, 42, 42],
+    );
+}
+
+#[test]
+fn test_brackets_and_literal() {
+    run_test_case(
+        "brackets and literal",
+        ": seconds-per-hour [ 60 60 * ] literal ;\nseconds-per-hour",
+        &[3600],
+    );
+}
+
+#[test]
+fn test_bracket_tick_execute() {
+    run_test_case(
+        "bracket tick execute",
+        ": square dup * ;\n: apply ['] square execute ;\n7 apply",
+        &[49],
+    );
+}
+
+#[test]
+fn test_tick_execute() {
+    run_test_case(
+        "tick execute",
+        ": square dup * ;\n' square 3 swap execute",
+        &[9],
+    );
+}
+
+#[test]
+fn test_literal_outside_definition() {
+    run_test_case_stdout(
+        "literal outside definition",
+        "1 literal",
+        "compile-only-word\n",
+        &[],
+    );
+}
+
+#[test]
+fn test_postpone_uses_definition_at_compile_time() {
+    run_test_case(
+        "postpone uses definition at compile time",
+        ": a 1 ;\n: b postpone a ; immediate\n: a 2 ;\n: c b ;\nc",
+        &[1],
+    );
+}
+
+#[test]
+fn test_postpone_structure_terminator() {
+    run_test_case(
+        "postpone structure terminator",
+        "\
+: endif postpone then ; immediate
+: check if 1 else 2 endif 3 ;
+-1 check
+0 check",
+        &[1, 3, 2, 3],
+    );
+}
+
+#[test]
+fn test_postpone_immediate_word() {
+    run_test_case(
+        "postpone immediate word",
+        ": five 5 postpone literal ; immediate\n: five-twice five postpone five ; immediate\n: f five-twice ;\nf",
+        &[5, 5],
+    );
+}
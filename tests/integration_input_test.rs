@@ -0,0 +1,33 @@
+mod common;
+
+use common::{cleanup_temp_file, create_temp_file, run_binary_with_stdin};
+
+fn run_with_stdin(test_name: &str, code: &str, input: &str) -> (String, String) {
+    let temp_file = create_temp_file(&format!("{}.fth", test_name), code);
+    let path = temp_file.to_str().expect("Ruta inválida");
+    let result = run_binary_with_stdin(test_name, &[path], input);
+    cleanup_temp_file(&temp_file);
+    result
+}
+
+#[test]
+fn test_key_reads_piped_data() {
+    let (output, stack) = run_with_stdin("key_piped", "key emit key emit key", "hi");
+    assert_eq!(output, "h i ");
+    assert_eq!(stack, "-1\n");
+}
+
+#[test]
+fn test_key_question_at_end_of_input() {
+    let (_, stack) = run_with_stdin("key_question", "key? key drop key?", "x");
+    assert_eq!(stack, "-1\n0\n");
+}
+
+#[test]
+fn test_accept_counts_lines() {
+    let code = "create line 80 allot
+: count-lines 0 begin key? while line 80 accept drop 1 + repeat ;
+count-lines";
+    let (_, stack) = run_with_stdin("accept_lines", code, "uno\ndos\n\ntres\n");
+    assert_eq!(stack, "4\n");
+}
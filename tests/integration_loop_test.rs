@@ -0,0 +1,86 @@
+mod common;
+
+use common::run_test_case;
+use common::run_test_case_stdout;
+
+#[test]
+fn test_do_loop_simple() {
+    run_test_case("do loop simple", ": f 3 0 do i loop ;\nf", &[0, 1, 2]);
+}
+
+#[test]
+fn test_do_loop_top_level() {
+    run_test_case("do loop top level", "4 1 do i loop", &[1, 2, 3]);
+}
+
+#[test]
+fn test_do_plus_loop() {
+    run_test_case("do plus loop", ": f 10 0 do i 3 +loop ;\nf", &[0, 3, 6, 9]);
+}
+
+#[test]
+fn test_do_plus_loop_negative() {
+    run_test_case(
+        "do plus loop negative",
+        ": f 0 3 do i -1 +loop ;\nf",
+        &[3, 2, 1, 0],
+    );
+}
+
+#[test]
+fn test_nested_do_loop_i_j() {
+    run_test_case(
+        "nested do loop i j",
+        ": f 2 0 do 2 0 do j 10 * i + loop loop ;\nf",
+        &[0, 1, 10, 11],
+    );
+}
+
+#[test]
+fn test_do_loop_leave() {
+    run_test_case(
+        "do loop leave",
+        ": f 10 0 do i dup 3 = if leave then loop ;\nf",
+        &[0, 1, 2, 3],
+    );
+}
+
+#[test]
+fn test_do_loop_unloop_exit() {
+    run_test_case(
+        "do loop unloop exit",
+        ": f 10 0 do i 2 = if i unloop exit then loop 99 ;\nf",
+        &[2],
+    );
+}
+
+#[test]
+fn test_do_loop_output() {
+    run_test_case_stdout(
+        "do loop output",
+        ": stars 0 do 42 emit loop ;\n3 stars",
+        "* * * ",
+        &[],
+    );
+}
+
+#[test]
+fn test_i_outside_loop() {
+    run_test_case_stdout("i outside loop", "i", "loop-stack-underflow\n", &[]);
+}
+
+#[test]
+fn test_leave_outside_loop() {
+    run_test_case_stdout(
+        "leave outside loop",
+        ": bar 1 leave 3 ;\nbar 4",
+        "Unexpected token in definition\n",
+        &[],
+    );
+    run_test_case_stdout(
+        "unloop outside loop",
+        ": bar 1 if unloop then ;",
+        "Unexpected token in definition\n",
+        &[],
+    );
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use common::run_test_case;
+use common::run_test_case_stdout;
+
+#[test]
+fn test_variable_store_fetch() {
+    run_test_case("variable store fetch", "variable x\n42 x !\nx @", &[42]);
+}
+
+#[test]
+fn test_variable_starts_at_zero() {
+    run_test_case("variable starts at zero", "variable x\nx @", &[0]);
+}
+
+#[test]
+fn test_variable_plus_store() {
+    run_test_case(
+        "variable plus store",
+        "variable total\n: add total +! ;\n3 add 4 add\ntotal @",
+        &[7],
+    );
+}
+
+#[test]
+fn test_constant() {
+    run_test_case(
+        "constant",
+        "60 constant minute\n: minutes minute * ;\n2 minutes",
+        &[120],
+    );
+}
+
+#[test]
+fn test_value_to() {
+    run_test_case("value to", "5 value v\nv 8 to v v", &[5, 8]);
+}
+
+#[test]
+fn test_value_to_in_definition() {
+    run_test_case(
+        "value to in definition",
+        "0 value counter\n: tick counter 1 + to counter ;\ntick tick tick counter",
+        &[3],
+    );
+}
+
+#[test]
+fn test_c_store_c_fetch() {
+    run_test_case(
+        "c store c fetch",
+        "variable x\n258 x !\nx c@\n7 x c! x @",
+        &[2, 263],
+    );
+}
+
+#[test]
+fn test_fetch_out_of_range() {
+    run_test_case_stdout("fetch out of range", "-1 @", "address-out-of-range\n", &[]);
+}
+
+#[test]
+fn test_to_non_value() {
+    run_test_case_stdout("to non value", "variable x\n1 to x", "invalid-word\n", &[]);
+}
+
+#[test]
+fn test_create_comma_table() {
+    run_test_case(
+        "create comma table",
+        "create primes 2 , 3 , 5 , 7 ,\n: prime cells primes + @ ;\n0 prime 3 prime",
+        &[2, 7],
+    );
+}
+
+#[test]
+fn test_create_allot_array() {
+    run_test_case(
+        "create allot array",
+        "create arr 4 cells allot\n: arr! cells arr + ! ;\n: arr@ cells arr + @ ;\n10 0 arr! 40 3 arr!\n0 arr@ 3 arr@",
+        &[10, 40],
+    );
+}
+
+#[test]
+fn test_here_allot() {
+    run_test_case(
+        "here allot",
+        "here 6 allot here swap -\nhere -6 allot here -",
+        &[6, 6],
+    );
+}
+
+#[test]
+fn test_c_comma_align() {
+    run_test_case(
+        "c comma align",
+        "create bytes 65 c, 66 c, 67 c,\nalign here bytes -\nbytes 2 + c@",
+        &[4, 67],
+    );
+}
+
+#[test]
+fn test_cell_plus() {
+    run_test_case("cell plus", "10 cell+ 3 cells", &[12, 6]);
+}
+
+#[test]
+fn test_allot_overflow() {
+    run_test_case_stdout(
+        "allot overflow",
+        "30000 allot",
+        "data-space-overflow\n",
+        &[],
+    );
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use common::cleanup_temp_file;
+use common::create_temp_file;
+use common::run_binary_with_file_args;
+use common::run_test_case_stdout;
+
+#[test]
+fn test_arithmetic_wraps_by_default() {
+    run_test_case_stdout(
+        "arithmetic wraps by default",
+        "32767 1 + . -32768 -1 / .",
+        "-32768 -32768 ",
+        &[],
+    );
+}
+
+#[test]
+fn test_checked_arithmetic_overflow() {
+    let temp_file = create_temp_file("test_checked_arithmetic_overflow.fth", "1 2 32767 1 +");
+    let output = run_binary_with_file_args(&temp_file, &["--checked-arithmetic"]);
+    assert_eq!(output, "arithmetic-overflow");
+    cleanup_temp_file(&temp_file);
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use common::cleanup_temp_file;
+use common::create_temp_file;
+use common::run_binary_with_file_args;
+use common::run_test_case;
+use common::run_test_case_stdout;
+use common::stack_file_arg;
+use std::process::Command;
+
+#[test]
+fn test_recurse_factorial() {
+    run_test_case(
+        "recurse factorial",
+        ": fact dup 1 > if dup 1 - recurse * then ;\n6 fact",
+        &[720],
+    );
+}
+
+#[test]
+fn test_recurse_fibonacci() {
+    run_test_case(
+        "recurse fibonacci",
+        ": fib dup 2 < if exit then dup 1 - recurse swap 2 - recurse + ;\n10 fib",
+        &[55],
+    );
+}
+
+#[test]
+fn test_recursive_marker() {
+    run_test_case(
+        "recursive marker",
+        ": countdown recursive dup if dup 1 - countdown then ;\n3 countdown",
+        &[3, 2, 1, 0],
+    );
+}
+
+#[test]
+fn test_recurse_keeps_original_after_redefinition() {
+    run_test_case(
+        "recurse keeps original after redefinition",
+        ": f dup if 1 - recurse then ;\n: g f ;\n: f 100 ;\n3 g",
+        &[0],
+    );
+}
+
+#[test]
+fn test_infinite_recursion() {
+    run_test_case_stdout(
+        "infinite recursion",
+        ": f recurse ;\nf",
+        "return-stack-overflow\n",
+        &[],
+    );
+}
+
+#[test]
+fn test_max_call_depth() {
+    let temp_file = create_temp_file(
+        "test_max_call_depth.fth",
+        ": f dup if 1 - recurse then ;\n10 f",
+    );
+    let output = run_binary_with_file_args(&temp_file, &["max-call-depth=5"]);
+    assert_eq!(
+        output.trim(),
+        "return-stack-overflow",
+        "Salida: {:?}",
+        output
+    );
+    cleanup_temp_file(&temp_file);
+}
+
+#[test]
+fn test_large_max_call_depth() {
+    for code in [
+        ": f recurse ;\nf",
+        ": g 1 if 1 0 do begin 1 while recurse repeat loop then ;\ng",
+    ] {
+        let temp_file = create_temp_file("test_large_max_call_depth.fth", code);
+        let output = run_binary_with_file_args(&temp_file, &["max-call-depth=20000"]);
+        assert_eq!(output, "return-stack-overflow", "Salida: {:?}", output);
+        cleanup_temp_file(&temp_file);
+    }
+}
+
+#[test]
+fn test_invalid_max_call_depth() {
+    let temp_file = create_temp_file("test_invalid_max_call_depth.fth", "1");
+    let output = Command::new(env!("CARGO_BIN_EXE_taller_tp_individual"))
+        .arg(&temp_file)
+        .arg(stack_file_arg(&temp_file))
+        .arg("max-call-depth=mucho")
+        .output()
+        .expect("Fallo al ejecutar el comando");
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("mucho"),
+        "Salida de error: {:?}",
+        output.stderr
+    );
+    cleanup_temp_file(&temp_file);
+}
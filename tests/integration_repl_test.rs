@@ -0,0 +1,59 @@
+mod common;
+
+use common::{cleanup_temp_file, create_temp_file, run_binary_with_stdin};
+
+#[test]
+fn test_repl_prints_ok_after_each_line() {
+    let (output, _) = run_binary_with_stdin("repl ok", &[], "1 2 +\n.\n");
+    assert_eq!(output, " ok\n3  ok\n");
+}
+
+#[test]
+fn test_repl_multiline_definition() {
+    let (output, _) =
+        run_binary_with_stdin("repl multiline", &[], ": square\n  dup * ;\n4 square .\n");
+    assert_eq!(output, " compiled\n ok\n16  ok\n");
+}
+
+#[test]
+fn test_repl_keeps_dictionary_after_error() {
+    let (output, stack) = run_binary_with_stdin(
+        "repl after error",
+        &["--repl"],
+        ": twice 2 * ;\n5 +\nfoo\n3 twice\n",
+    );
+    assert_eq!(output, " ok\n stack-underflow\n ?\n ok\n");
+    assert_eq!(stack, "6\n");
+}
+
+#[test]
+fn test_repl_after_file() {
+    let temp_file = create_temp_file("repl_after_file.fth", ": cube dup dup * * ;");
+    let path = temp_file.to_str().expect("Ruta inválida");
+    let (output, _) = run_binary_with_stdin("repl after file", &[path, "--repl"], "2 cube .\n");
+    assert_eq!(output, "8  ok\n");
+    cleanup_temp_file(&temp_file);
+}
+
+#[test]
+fn test_repl_key_reads_following_input() {
+    let (output, _) = run_binary_with_stdin("repl key", &[], "KEY .\nA2 3 + .\n");
+    assert_eq!(output, "65  ok\n5  ok\n");
+}
+
+#[test]
+fn test_repl_key_question() {
+    let (output, _) = run_binary_with_stdin("repl key question", &[], "KEY? . KEY .\nA\n");
+    assert_eq!(output, "-1 65  ok\n ok\n");
+}
+
+#[test]
+fn test_repl_keeps_file_definitions_after_error() {
+    let temp_file = create_temp_file("repl_after_file_error.fth", ": sq dup * ;\n1 0 /");
+    let path = temp_file.to_str().expect("Ruta inválida");
+    let (output, stack) =
+        run_binary_with_stdin("repl after file error", &[path, "--repl"], "3 sq .\n");
+    assert_eq!(output, "division-by-zero\n9  ok\n");
+    assert_eq!(stack, "");
+    cleanup_temp_file(&temp_file);
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::cleanup_temp_file;
+use common::create_temp_file;
+use common::run_binary_with_file_args;
+use common::run_test_case;
+use common::run_test_case_stdout;
+
+#[test]
+fn test_to_r_r_from() {
+    run_test_case("to r r from", "1 2 >r 3 r>", &[1, 3, 2]);
+}
+
+#[test]
+fn test_r_fetch() {
+    run_test_case("r fetch", ": f >r r@ r@ r> + + ;\n5 f", &[15]);
+}
+
+#[test]
+fn test_two_to_r_two_r_from() {
+    run_test_case("two to r two r from", "1 2 3 2>r 4 2r>", &[1, 4, 2, 3]);
+}
+
+#[test]
+fn test_return_stack_inside_loop() {
+    run_test_case(
+        "return stack inside loop",
+        ": f 0 >r 4 0 do r> i + >r loop r> ;\nf",
+        &[6],
+    );
+}
+
+#[test]
+fn test_return_stack_overflow() {
+    let temp_file = create_temp_file("test_return_stack_overflow.fth", "1 >r 2 >r");
+    let output = run_binary_with_file_args(&temp_file, &["return-stack-size=2"]);
+    assert_eq!(
+        output.trim(),
+        "return-stack-overflow",
+        "Salida: {:?}",
+        output
+    );
+    cleanup_temp_file(&temp_file);
+}
+
+#[test]
+fn test_return_stack_underflow() {
+    run_test_case_stdout(
+        "return stack underflow",
+        "r>",
+        "return-stack-underflow\n",
+        &[],
+    );
+}
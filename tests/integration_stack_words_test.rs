@@ -0,0 +1,50 @@
+mod common;
+
+use common::run_test_case;
+use common::run_test_case_stdout;
+
+#[test]
+fn test_nip_tuck() {
+    run_test_case("nip tuck", "1 2 nip 3 tuck", &[3, 2, 3]);
+}
+
+#[test]
+fn test_pick() {
+    run_test_case("pick", "1 2 3 0 pick 3 pick", &[1, 2, 3, 3, 1]);
+}
+
+#[test]
+fn test_roll() {
+    run_test_case("roll", "1 2 3 4 3 roll 0 roll", &[2, 3, 4, 1]);
+}
+
+#[test]
+fn test_minus_rot() {
+    run_test_case("minus rot", "1 2 3 -rot", &[3, 1, 2]);
+}
+
+#[test]
+fn test_question_dup() {
+    run_test_case("question dup", "0 ?dup 5 ?dup", &[0, 5, 5]);
+}
+
+#[test]
+fn test_two_cell_words() {
+    run_test_case(
+        "two cell words",
+        "1 2 2dup 3 4 2swap 2over 2drop",
+        &[1, 2, 3, 4, 1, 2],
+    );
+}
+
+#[test]
+fn test_depth() {
+    run_test_case("depth", "depth 7 8 depth", &[0, 7, 8, 3]);
+}
+
+#[test]
+fn test_pick_roll_underflow() {
+    run_test_case_stdout("pick underflow", "1 2 2 pick", "stack-underflow\n", &[]);
+    run_test_case_stdout("roll underflow", "1 2 -1 roll", "stack-underflow\n", &[]);
+    run_test_case_stdout("two drop underflow", "1 2drop", "stack-underflow\n", &[]);
+}
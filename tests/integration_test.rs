@@ -2,9 +2,9 @@ mod common;
 
 use common::cleanup_temp_file;
 use common::create_temp_file;
+use common::read_stack_output;
 use common::run_binary_with_file;
 use common::run_binary_with_file_args;
-use std::fs;
 
 #[test]
 fn test_emit() {
@@ -525,12 +525,11 @@ fn test_output_format() {
     let expected_stdout = vec!["5 4".to_string(), "3".to_string()];
     assert_eq!(output_lines, expected_stdout, "STDOUT: {:?}", output_lines);
 
-    let file_content = fs::read_to_string("stack.fth").expect("No se pudo leer stack.fth");
-    let file_lines: Vec<String> = file_content.lines().map(|l| l.trim().to_string()).collect();
+    let file_lines = read_stack_output(&temp_file);
     let expected_stack = vec!["1".to_string(), "2".to_string()];
     assert_eq!(
         file_lines, expected_stack,
-        "Contenido de stack.fth: {:?}",
+        "Contenido del archivo de la pila: {:?}",
         file_lines
     );
 